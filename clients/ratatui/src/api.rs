@@ -10,7 +10,11 @@ use anyhow::{Context, Result};
 use reqwest::Client;
 use serde::Deserialize;
 
-use crate::types::{AgentSnapshot, KeyRequest, TextInputRequest};
+use crate::types::{
+    AgentSnapshot, BootstrapResponse, BranchListResponse, DeleteWorktreeRequest, KeyRequest,
+    PromptRequest, PromptResponse, SpawnRequest, SpawnResponse, SpawnWorktreeRequest,
+    TextInputRequest, TranscriptRecord, TranscriptResponse, WorktreeSnapshot,
+};
 
 /// Port + bearer token, as written by tmai-core.
 #[derive(Debug, Clone, Deserialize)]
@@ -87,6 +91,44 @@ impl ApiClient {
             .context("decode /agents body")
     }
 
+    /// Open `GET /api/events` and check it answers as an SSE stream,
+    /// without reading any events.
+    pub async fn probe_events(&self) -> Result<()> {
+        let resp = self
+            .http
+            .get(self.url("/events"))
+            .bearer_auth(&self.token)
+            .send()
+            .await
+            .context("GET /events")?;
+        let resp = ensure_ok(resp).await?;
+        let content_type = resp
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or("");
+        if !content_type.starts_with("text/event-stream") {
+            anyhow::bail!("unexpected content type {content_type:?}");
+        }
+        Ok(())
+    }
+
+    /// `GET /api/bootstrap` — the consistent snapshot set tmai-core
+    /// serves to freshly (re)connected clients.
+    pub async fn bootstrap(&self) -> Result<BootstrapResponse> {
+        let resp = self
+            .http
+            .get(self.url("/bootstrap"))
+            .bearer_auth(&self.token)
+            .send()
+            .await
+            .context("GET /bootstrap")?;
+        let resp = ensure_ok(resp).await?;
+        resp.json::<BootstrapResponse>()
+            .await
+            .context("decode /bootstrap body")
+    }
+
     /// `POST /api/agents/{id}/approve`
     pub async fn approve(&self, id: &str) -> Result<()> {
         let resp = self
@@ -114,6 +156,22 @@ impl ApiClient {
         Ok(())
     }
 
+    /// `POST /api/agents/{id}/prompt` — delivered once the agent is ready.
+    pub async fn send_prompt(&self, id: &str, prompt: &str) -> Result<PromptResponse> {
+        let resp = self
+            .http
+            .post(self.url(&format!("/agents/{id}/prompt")))
+            .bearer_auth(&self.token)
+            .json(&PromptRequest { prompt })
+            .send()
+            .await
+            .context("POST prompt")?;
+        let resp = ensure_ok(resp).await?;
+        resp.json::<PromptResponse>()
+            .await
+            .context("decode /prompt body")
+    }
+
     /// `POST /api/agents/{id}/key`
     pub async fn send_key(&self, id: &str, key: &str) -> Result<()> {
         let resp = self
@@ -128,6 +186,141 @@ impl ApiClient {
         Ok(())
     }
 
+    /// `GET /api/agents/{id}/transcript`
+    pub async fn transcript(&self, id: &str) -> Result<Vec<TranscriptRecord>> {
+        let resp = self
+            .http
+            .get(self.url(&format!("/agents/{id}/transcript")))
+            .bearer_auth(&self.token)
+            .send()
+            .await
+            .context("GET transcript")?;
+        let resp = ensure_ok(resp).await?;
+        let body = resp
+            .json::<TranscriptResponse>()
+            .await
+            .context("decode transcript body")?;
+        Ok(body.records)
+    }
+
+    /// `GET /api/settings/{section}` — one config table as tmai-core
+    /// serves it. Kept untyped so settings this client doesn't know pass
+    /// through untouched.
+    pub async fn get_settings(&self, section: &str) -> Result<serde_json::Value> {
+        let resp = self
+            .http
+            .get(self.url(&format!("/settings/{section}")))
+            .bearer_auth(&self.token)
+            .send()
+            .await
+            .with_context(|| format!("GET /settings/{section}"))?;
+        let resp = ensure_ok(resp).await?;
+        resp.json::<serde_json::Value>()
+            .await
+            .with_context(|| format!("decode /settings/{section} body"))
+    }
+
+    /// `PUT /api/settings/{section}` with a partial body: only the keys
+    /// present are changed.
+    pub async fn update_settings(&self, section: &str, patch: &serde_json::Value) -> Result<()> {
+        let resp = self
+            .http
+            .put(self.url(&format!("/settings/{section}")))
+            .bearer_auth(&self.token)
+            .json(patch)
+            .send()
+            .await
+            .with_context(|| format!("PUT /settings/{section}"))?;
+        ensure_ok(resp).await?;
+        Ok(())
+    }
+
+    /// `GET /api/git/branches?repo=` — branches of the repository at
+    /// `repo` with ahead/behind and upstream tracking.
+    pub async fn git_branches(&self, repo: &str) -> Result<BranchListResponse> {
+        let resp = self
+            .http
+            .get(self.url("/git/branches"))
+            .query(&[("repo", repo)])
+            .bearer_auth(&self.token)
+            .send()
+            .await
+            .context("GET /git/branches")?;
+        let resp = ensure_ok(resp).await?;
+        resp.json::<BranchListResponse>()
+            .await
+            .context("decode /git/branches body")
+    }
+
+    /// `GET /api/worktrees`
+    pub async fn list_worktrees(&self) -> Result<Vec<WorktreeSnapshot>> {
+        let resp = self
+            .http
+            .get(self.url("/worktrees"))
+            .bearer_auth(&self.token)
+            .send()
+            .await
+            .context("GET /worktrees")?;
+        let resp = ensure_ok(resp).await?;
+        resp.json::<Vec<WorktreeSnapshot>>()
+            .await
+            .context("decode /worktrees body")
+    }
+
+    /// `POST /api/spawn` — launch a command in a new PTY.
+    pub async fn spawn(&self, command: &str, args: &[String], cwd: &str) -> Result<SpawnResponse> {
+        let resp = self
+            .http
+            .post(self.url("/spawn"))
+            .bearer_auth(&self.token)
+            .json(&SpawnRequest { command, args, cwd })
+            .send()
+            .await
+            .context("POST spawn")?;
+        let resp = ensure_ok(resp).await?;
+        resp.json::<SpawnResponse>()
+            .await
+            .context("decode /spawn body")
+    }
+
+    /// `POST /api/spawn/worktree` — new worktree + branch with an agent.
+    pub async fn spawn_worktree(&self, name: &str, cwd: &str) -> Result<()> {
+        let resp = self
+            .http
+            .post(self.url("/spawn/worktree"))
+            .bearer_auth(&self.token)
+            .json(&SpawnWorktreeRequest { name, cwd })
+            .send()
+            .await
+            .context("POST spawn/worktree")?;
+        ensure_ok(resp).await?;
+        Ok(())
+    }
+
+    /// `POST /api/worktrees/delete` — removes the worktree and its branch.
+    /// `force` discards uncommitted changes.
+    pub async fn delete_worktree(
+        &self,
+        repo_path: &str,
+        worktree_name: &str,
+        force: bool,
+    ) -> Result<()> {
+        let resp = self
+            .http
+            .post(self.url("/worktrees/delete"))
+            .bearer_auth(&self.token)
+            .json(&DeleteWorktreeRequest {
+                repo_path,
+                worktree_name,
+                force,
+            })
+            .send()
+            .await
+            .context("POST worktrees/delete")?;
+        ensure_ok(resp).await?;
+        Ok(())
+    }
+
     /// `POST /api/agents/{id}/kill`
     pub async fn kill(&self, id: &str) -> Result<()> {
         let resp = self
@@ -0,0 +1,170 @@
+//! Client-side configuration, read from `~/.config/tmai/ratatui.toml`.
+//!
+//! This file belongs to the ratatui client only — tmai-core's own
+//! `config.toml` lives next to it and is never read here. A missing file
+//! is not an error: every table has defaults.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+
+use crate::hooks::HooksConfig;
+use crate::redact::{RedactionConfig, Redactor};
+use crate::ui::keymap::{KeySpec, Keymap};
+use crate::ui::presets::{self, Preset};
+use crate::ui::templates::{self, Template};
+
+/// Raw TOML shape. Unknown tables are tolerated so a newer config file
+/// doesn't break an older client build.
+#[derive(Debug, Default, Deserialize)]
+struct RawConfig {
+    #[serde(default)]
+    keys: BTreeMap<String, KeySpec>,
+    #[serde(default)]
+    redaction: RedactionConfig,
+    #[serde(default)]
+    hooks: HooksConfig,
+    #[serde(default)]
+    presets: Vec<Preset>,
+    #[serde(default)]
+    prompts: Vec<Template>,
+}
+
+/// Validated client configuration.
+#[derive(Debug, Clone, Default)]
+pub struct Config {
+    pub keymap: Keymap,
+    pub redactor: Redactor,
+    pub hooks: HooksConfig,
+    /// `[[presets]]` for the spawn overlay, in file order.
+    pub presets: Vec<Preset>,
+    /// `[[prompts]]` templates, listed before those in the prompts dir.
+    pub prompts: Vec<Template>,
+}
+
+/// Default location: `$XDG_CONFIG_HOME/tmai/ratatui.toml`.
+pub fn default_path() -> Option<PathBuf> {
+    dirs::config_dir().map(|d| d.join("tmai").join("ratatui.toml"))
+}
+
+impl Config {
+    /// Load from `path`, or from [`default_path`] when `None`. Only an
+    /// explicitly given path is required to exist.
+    pub fn load(path: Option<&Path>) -> Result<Self> {
+        let (path, required) = match path {
+            Some(p) => (p.to_path_buf(), true),
+            None => match default_path() {
+                Some(p) => (p, false),
+                None => return Ok(Self::default()),
+            },
+        };
+        let raw = match std::fs::read_to_string(&path) {
+            Ok(raw) => raw,
+            Err(e) if !required && e.kind() == std::io::ErrorKind::NotFound => {
+                return Ok(Self::default())
+            }
+            Err(e) => return Err(e).with_context(|| format!("read {}", path.display())),
+        };
+        Self::parse(&raw).with_context(|| format!("parse {}", path.display()))
+    }
+
+    pub fn parse(raw: &str) -> Result<Self> {
+        let raw: RawConfig = toml::from_str(raw)?;
+        let keymap = Keymap::from_overrides(&raw.keys)?;
+        let redactor = Redactor::new(&raw.redaction)?;
+        raw.hooks.validate()?;
+        presets::validate(&raw.presets)?;
+        templates::validate(&raw.prompts)?;
+        Ok(Self {
+            keymap,
+            redactor,
+            hooks: raw.hooks,
+            presets: raw.presets,
+            prompts: raw.prompts,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ui::keymap::Action;
+
+    #[test]
+    fn empty_file_yields_defaults() {
+        let cfg = Config::parse("").unwrap();
+        assert_eq!(cfg.keymap.hint(Action::Approve), "a");
+    }
+
+    #[test]
+    fn keys_table_accepts_string_or_array() {
+        let cfg = Config::parse(
+            r#"
+            [keys]
+            approve = "Enter"
+            select_next = ["Ctrl+n", "Down"]
+            "#,
+        )
+        .unwrap();
+        assert_eq!(cfg.keymap.hint(Action::Approve), "Enter");
+        assert_eq!(cfg.keymap.hint(Action::SelectNext), "Ctrl+n/↓");
+    }
+
+    #[test]
+    fn conflicting_keys_fail_to_load() {
+        let err = Config::parse("[keys]\nquit = \"a\"\n").unwrap_err();
+        assert!(format!("{err:#}").contains("bound to both"), "{err:#}");
+    }
+
+    #[test]
+    fn hooks_table_rejects_unknown_keys() {
+        let cfg = Config::parse("[hooks]\non_halted = \"notify-send tmai\"\n").unwrap();
+        assert_eq!(cfg.hooks.on_halted.as_deref(), Some("notify-send tmai"));
+        assert_eq!(cfg.hooks.timeout_secs, 10);
+        assert!(Config::parse("[hooks]\non_halt = \"x\"\n").is_err());
+        assert!(Config::parse("[hooks]\nmax_concurrent = 0\n").is_err());
+    }
+
+    #[test]
+    fn presets_keep_file_order() {
+        let cfg = Config::parse(
+            r#"
+            [[presets]]
+            name = "reviewer"
+            command = "claude"
+            args = ["--model", "opus"]
+
+            [[presets]]
+            name = "codex"
+            command = "codex"
+            "#,
+        )
+        .unwrap();
+        let names: Vec<_> = cfg.presets.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, ["reviewer", "codex"]);
+        assert_eq!(cfg.presets[0].args, ["--model", "opus"]);
+    }
+
+    #[test]
+    fn prompts_parse_and_reject_duplicates() {
+        let cfg = Config::parse(
+            r#"
+            [[prompts]]
+            name = "ship"
+            text = "Commit, push and open a PR with a summary."
+            "#,
+        )
+        .unwrap();
+        assert_eq!(cfg.prompts[0].name, "ship");
+        let dup =
+            "[[prompts]]\nname = \"a\"\ntext = \"x\"\n[[prompts]]\nname = \"a\"\ntext = \"y\"\n";
+        assert!(Config::parse(dup).is_err());
+    }
+
+    #[test]
+    fn unknown_tables_are_tolerated() {
+        Config::parse("[future]\nflag = true\n").unwrap();
+    }
+}
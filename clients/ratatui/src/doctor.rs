@@ -0,0 +1,281 @@
+//! `tmai-ratatui doctor`: environment checks with suggested fixes.
+//!
+//! Most "the TUI shows nothing" reports come down to discovery or
+//! permissions rather than bugs, so each check here names what it looked
+//! at and, when it fails, what to do about it. Checks run in order and
+//! later ones are skipped when they depend on an earlier failure.
+
+use std::path::Path;
+use std::time::Instant;
+
+use crate::api::{self, ApiClient};
+use crate::config::{self, Config};
+use crate::{export, pins};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    Ok(String),
+    Warn(String),
+    Fail(String),
+}
+
+#[derive(Debug, Clone)]
+pub struct Check {
+    pub name: &'static str,
+    pub outcome: Outcome,
+    /// What to do when the check didn't pass.
+    pub fix: Option<String>,
+}
+
+impl Check {
+    fn ok(name: &'static str, detail: impl Into<String>) -> Self {
+        Self {
+            name,
+            outcome: Outcome::Ok(detail.into()),
+            fix: None,
+        }
+    }
+
+    fn warn(name: &'static str, detail: impl Into<String>, fix: impl Into<String>) -> Self {
+        Self {
+            name,
+            outcome: Outcome::Warn(detail.into()),
+            fix: Some(fix.into()),
+        }
+    }
+
+    fn fail(name: &'static str, detail: impl Into<String>, fix: impl Into<String>) -> Self {
+        Self {
+            name,
+            outcome: Outcome::Fail(detail.into()),
+            fix: Some(fix.into()),
+        }
+    }
+
+    /// `✓ name: detail`, plus an indented fix line when there is one.
+    pub fn render(&self) -> String {
+        let (mark, detail) = match &self.outcome {
+            Outcome::Ok(d) => ("✓", d),
+            Outcome::Warn(d) => ("!", d),
+            Outcome::Fail(d) => ("✗", d),
+        };
+        let mut out = format!("{mark} {}: {detail}", self.name);
+        if let Some(fix) = &self.fix {
+            out.push_str(&format!("\n    → {fix}"));
+        }
+        out
+    }
+}
+
+/// Where the connection under test comes from.
+pub enum Target {
+    /// `--url` and `--token` given; api.json isn't consulted.
+    Explicit(ApiClient),
+    /// Discovered from api.json, optionally with an overriding `--url`.
+    Discover { url: Option<String> },
+}
+
+/// Run every check. `config_path` is the `--config` argument.
+pub async fn run(config_path: Option<&Path>, target: Target) -> Vec<Check> {
+    let mut checks = vec![check_config(config_path)];
+    let client = match target {
+        Target::Explicit(client) => Some(client),
+        Target::Discover { url } => {
+            let (check, info) = check_api_json();
+            checks.push(check);
+            info.map(|info| {
+                let base = url.unwrap_or_else(|| format!("http://127.0.0.1:{}", info.port));
+                ApiClient::new(base, info.token)
+            })
+        }
+    };
+    if let Some(client) = client {
+        let reachable = check_agents(&client).await;
+        let ok = matches!(reachable.outcome, Outcome::Ok(_));
+        checks.push(reachable);
+        if ok {
+            checks.push(check_events(&client).await);
+        }
+    }
+    if let Some(path) = pins::default_path() {
+        if let Some(dir) = path.parent() {
+            checks.push(check_writable("state dir", dir));
+        }
+    }
+    checks.push(check_writable("export dir", &export::default_dir()));
+    checks
+}
+
+/// Whether any check failed outright.
+pub fn failed(checks: &[Check]) -> bool {
+    checks.iter().any(|c| matches!(c.outcome, Outcome::Fail(_)))
+}
+
+fn check_config(path: Option<&Path>) -> Check {
+    const NAME: &str = "config";
+    let shown = path
+        .map(Path::to_path_buf)
+        .or_else(config::default_path)
+        .map(|p| p.display().to_string())
+        .unwrap_or_else(|| "(no config dir)".into());
+    match Config::load(path) {
+        Ok(_) => Check::ok(NAME, shown),
+        Err(e) => Check::fail(
+            NAME,
+            format!("{e:#}"),
+            "fix or remove the file; a missing default config is fine",
+        ),
+    }
+}
+
+fn check_api_json() -> (Check, Option<api::ApiConnectionInfo>) {
+    const NAME: &str = "discovery";
+    let Some(path) = api::api_info_path() else {
+        return (
+            Check::fail(
+                NAME,
+                "XDG_RUNTIME_DIR is unset",
+                "export XDG_RUNTIME_DIR, or pass --url and --token",
+            ),
+            None,
+        );
+    };
+    let info = match api::load_connection_info() {
+        Ok(info) => info,
+        Err(e) => {
+            return (
+                Check::fail(
+                    NAME,
+                    format!("{e:#}"),
+                    "start tmai-core; it writes api.json on startup",
+                ),
+                None,
+            )
+        }
+    };
+    let detail = format!("{} (port {})", path.display(), info.port);
+    let check = match too_open(&path) {
+        Some(mode) => Check::warn(
+            NAME,
+            format!("{detail}, mode {mode:o}"),
+            format!(
+                "the file holds the bearer token: chmod 600 {}",
+                path.display()
+            ),
+        ),
+        None => Check::ok(NAME, detail),
+    };
+    (check, Some(info))
+}
+
+/// The file's mode when group or others can read it.
+#[cfg(unix)]
+fn too_open(path: &Path) -> Option<u32> {
+    use std::os::unix::fs::PermissionsExt;
+    let mode = std::fs::metadata(path).ok()?.permissions().mode() & 0o777;
+    (mode & 0o077 != 0).then_some(mode)
+}
+
+#[cfg(not(unix))]
+fn too_open(_path: &Path) -> Option<u32> {
+    None
+}
+
+async fn check_agents(client: &ApiClient) -> Check {
+    const NAME: &str = "api";
+    let started = Instant::now();
+    match client.list_agents().await {
+        Ok(agents) => Check::ok(
+            NAME,
+            format!(
+                "{} answered GET /api/agents with {} agent(s) in {}ms",
+                client.base_url(),
+                agents.len(),
+                started.elapsed().as_millis()
+            ),
+        ),
+        Err(e) => {
+            let msg = format!("{e:#}");
+            let fix = if msg.contains("401") || msg.contains("403") {
+                "the token was rejected; api.json may be stale, restart tmai-core or pass --token"
+            } else {
+                "is tmai-core running and listening on this address?"
+            };
+            Check::fail(NAME, format!("{}: {msg}", client.base_url()), fix)
+        }
+    }
+}
+
+async fn check_events(client: &ApiClient) -> Check {
+    const NAME: &str = "events";
+    match client.probe_events().await {
+        Ok(()) => Check::ok(NAME, "GET /api/events opened an SSE stream"),
+        Err(e) => Check::fail(
+            NAME,
+            format!("{e:#}"),
+            "a proxy in front of tmai-core may be buffering or blocking SSE",
+        ),
+    }
+}
+
+/// Create `dir` if needed and write and remove a probe file in it.
+fn check_writable(name: &'static str, dir: &Path) -> Check {
+    let probe = dir.join(format!(".tmai-doctor-{}", std::process::id()));
+    let result = std::fs::create_dir_all(dir)
+        .and_then(|()| std::fs::write(&probe, b""))
+        .and_then(|()| std::fs::remove_file(&probe));
+    match result {
+        Ok(()) => Check::ok(name, dir.display().to_string()),
+        Err(e) => Check::fail(
+            name,
+            format!("{}: {e}", dir.display()),
+            "check ownership and permissions of the directory",
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn writable_dir_passes_and_file_path_fails() {
+        let dir = std::env::temp_dir().join(format!("tmai-doctor-{}", std::process::id()));
+        let check = check_writable("dir", &dir.join("nested"));
+        assert!(
+            matches!(check.outcome, Outcome::Ok(_)),
+            "{}",
+            check.render()
+        );
+        // A regular file where the directory should be.
+        let file = dir.join("file");
+        std::fs::write(&file, b"").unwrap();
+        let check = check_writable("dir", &file);
+        assert!(matches!(check.outcome, Outcome::Fail(_)));
+        assert!(failed(&[check]));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn render_adds_fix_line() {
+        let c = Check::warn("discovery", "mode 644", "chmod 600 api.json");
+        assert_eq!(
+            c.render(),
+            "! discovery: mode 644\n    → chmod 600 api.json"
+        );
+        assert_eq!(Check::ok("api", "fine").render(), "✓ api: fine");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn world_readable_file_is_too_open() {
+        use std::os::unix::fs::PermissionsExt;
+        let path = std::env::temp_dir().join(format!("tmai-doctor-mode-{}", std::process::id()));
+        std::fs::write(&path, b"{}").unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o644)).unwrap();
+        assert_eq!(too_open(&path), Some(0o644));
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600)).unwrap();
+        assert_eq!(too_open(&path), None);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
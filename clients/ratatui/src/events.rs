@@ -1,9 +1,10 @@
 //! SSE client for `/api/events`.
 //!
 //! tmai-core emits named SSE events — `agents`, `teams`, `teammate_idle`,
-//! `usage`, `worktree_created`, etc. This milestone only wires the
-//! `agents` event (full AgentSnapshot[] snapshot). Other events are
-//! observed but ignored — forward-compat per the tmai-react rule.
+//! `usage`, `worktree_created`, etc. The `agents` event (full
+//! AgentSnapshot[] snapshot) and the `RuntimeUpdate` envelope (vendor
+//! availability) are wired. Other events are observed but ignored —
+//! forward-compat per the tmai-react rule.
 
 use anyhow::Result;
 use futures_util::StreamExt;
@@ -11,18 +12,30 @@ use reqwest_eventsource::{Event as SseEvent, EventSource};
 use tokio::sync::mpsc;
 
 use crate::api::ApiClient;
-use crate::types::AgentSnapshot;
+use crate::types::{
+    AgentSnapshot, BranchListResponse, RuntimeUpdate, VendorStatus, WorktreeSnapshot,
+};
 
-/// A decoded SSE event that matters to the UI layer.
+/// An event for the UI loop: a decoded SSE event that matters to it, or
+/// the result of a fetch run off the loop.
 #[derive(Debug, Clone)]
 pub enum AppEvent {
     /// New full snapshot of the agent list.
     Agents(Vec<AgentSnapshot>),
+    /// New per-vendor availability (rate limits, outages).
+    Vendors(Vec<VendorStatus>),
     /// Transport-level reconnect — UI should refetch state on its own
     /// cadence (e.g. trigger a `GET /agents` to recover missed deltas).
     Reconnected,
     /// Transport gave up after repeated failures.
     Disconnected(String),
+    /// `/git/branches` for `repo`; `None` when the request failed.
+    GitBranches {
+        repo: String,
+        info: Option<BranchListResponse>,
+    },
+    /// The worktree list; empty when the request failed.
+    Worktrees(Vec<WorktreeSnapshot>),
 }
 
 /// Start the SSE consumer task. Sends decoded events to `tx` until
@@ -48,19 +61,27 @@ pub fn spawn(client: ApiClient, tx: mpsc::UnboundedSender<AppEvent>) {
                     let _ = tx.send(AppEvent::Reconnected);
                 }
                 Ok(SseEvent::Message(msg)) => {
-                    if msg.event == "agents" {
-                        match serde_json::from_str::<Vec<AgentSnapshot>>(&msg.data) {
-                            Ok(agents) => {
-                                if tx.send(AppEvent::Agents(agents)).is_err() {
-                                    break;
-                                }
-                            }
-                            Err(e) => {
-                                tracing::warn!("decode agents SSE: {e}");
+                    let decoded = match msg.event.as_str() {
+                        "agents" => serde_json::from_str::<Vec<AgentSnapshot>>(&msg.data)
+                            .map(|agents| Some(AppEvent::Agents(agents))),
+                        // A `Removed` change carries no snapshot; nothing
+                        // to show until the next upsert.
+                        "RuntimeUpdate" => serde_json::from_str::<RuntimeUpdate>(&msg.data)
+                            .map(|u| u.snapshot.map(|s| AppEvent::Vendors(s.vendors))),
+                        // Other named events are ignored at this milestone.
+                        _ => Ok(None),
+                    };
+                    match decoded {
+                        Ok(Some(ev)) => {
+                            if tx.send(ev).is_err() {
+                                break;
                             }
                         }
+                        Ok(None) => {}
+                        Err(e) => {
+                            tracing::warn!("decode {} SSE: {e}", msg.event);
+                        }
                     }
-                    // Other named events are ignored at this milestone.
                 }
                 Err(err) => {
                     // reqwest-eventsource auto-reconnects on transient
@@ -80,3 +101,10 @@ pub fn spawn(client: ApiClient, tx: mpsc::UnboundedSender<AppEvent>) {
 pub async fn backfill(client: &ApiClient) -> Result<Vec<AgentSnapshot>> {
     client.list_agents().await
 }
+
+/// Vendor availability backfill from `/api/bootstrap`. Older tmai-core
+/// builds without a runtime snapshot yield an empty list.
+pub async fn backfill_vendors(client: &ApiClient) -> Result<Vec<VendorStatus>> {
+    let body = client.bootstrap().await?;
+    Ok(body.snapshots.runtime.unwrap_or_default().vendors)
+}
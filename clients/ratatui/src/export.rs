@@ -0,0 +1,215 @@
+//! Transcript export to Markdown.
+//!
+//! Post-mortems need an agent's conversation as a file that can be
+//! attached to an issue. Records are expected to be redacted already
+//! (the UI exports exactly what the history view shows); this module only
+//! formats and writes them under `$XDG_DATA_HOME/tmai/transcripts/`.
+
+use std::fmt::Write as _;
+use std::fs::{File, OpenOptions};
+use std::io::Write as _;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Local};
+
+use crate::types::TranscriptRecord;
+
+/// Default export directory; falls back to the working directory when
+/// no data dir can be resolved.
+pub fn default_dir() -> PathBuf {
+    dirs::data_dir()
+        .map(|d| d.join("tmai").join("transcripts"))
+        .unwrap_or_else(|| PathBuf::from("."))
+}
+
+/// Render `records` as a Markdown document.
+pub fn to_markdown(agent_id: &str, records: &[TranscriptRecord], now: DateTime<Local>) -> String {
+    let mut out = format!(
+        "# Transcript — {agent_id}\n\n_Exported {}_\n",
+        now.format("%Y-%m-%d %H:%M:%S %z")
+    );
+    for record in records {
+        match record {
+            TranscriptRecord::User { text, timestamp } => {
+                heading(&mut out, "User", timestamp.as_deref());
+                let _ = writeln!(out, "{}", text.trim_end());
+            }
+            TranscriptRecord::AssistantText { text, timestamp } => {
+                heading(&mut out, "Assistant", timestamp.as_deref());
+                let _ = writeln!(out, "{}", text.trim_end());
+            }
+            TranscriptRecord::Thinking { text, .. } => {
+                out.push('\n');
+                for line in text.trim_end().lines() {
+                    let _ = writeln!(out, "> {line}");
+                }
+            }
+            TranscriptRecord::ToolUse {
+                tool_name,
+                input_summary,
+                input_full,
+                timestamp,
+            } => {
+                heading(
+                    &mut out,
+                    &format!("Tool: {tool_name}"),
+                    timestamp.as_deref(),
+                );
+                if !input_summary.is_empty() {
+                    let _ = writeln!(out, "`{}`", input_summary.replace('`', "'"));
+                }
+                if let Some(input) = input_full {
+                    let json = serde_json::to_string_pretty(input).unwrap_or_default();
+                    fenced(&mut out, "json", &json);
+                }
+            }
+            TranscriptRecord::ToolResult {
+                output_summary,
+                is_error,
+                ..
+            } => {
+                if *is_error {
+                    out.push_str("\n**Error:**\n");
+                }
+                fenced(&mut out, "", output_summary);
+            }
+            TranscriptRecord::Unknown => {}
+        }
+    }
+    out
+}
+
+fn heading(out: &mut String, title: &str, timestamp: Option<&str>) {
+    match timestamp {
+        Some(t) => {
+            let _ = writeln!(out, "\n## {title} · {t}\n");
+        }
+        None => {
+            let _ = writeln!(out, "\n## {title}\n");
+        }
+    }
+}
+
+/// Code fence long enough that backticks inside `body` can't close it.
+fn fenced(out: &mut String, lang: &str, body: &str) {
+    let longest = body.split(|c| c != '`').map(str::len).max().unwrap_or(0);
+    let fence = "`".repeat(longest.max(2) + 1);
+    let _ = write!(out, "\n{fence}{lang}\n{}\n{fence}\n", body.trim_end());
+}
+
+/// Write the Markdown export into `dir` and return the file path. The
+/// name is the agent id with path-hostile characters replaced, plus a
+/// timestamp; a second export within the same second gets a `-2`, `-3`, …
+/// suffix, so repeated exports never overwrite each other. The file can
+/// hold whatever the redaction missed, so on Unix only the owner can read
+/// it.
+pub fn write_markdown(dir: &Path, agent_id: &str, records: &[TranscriptRecord]) -> Result<PathBuf> {
+    let now = Local::now();
+    std::fs::create_dir_all(dir).with_context(|| format!("create {}", dir.display()))?;
+    let stem: String = agent_id
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    let base = format!("{stem}-{}", now.format("%Y%m%d-%H%M%S"));
+    let mut n = 1;
+    let (path, mut file) = loop {
+        let name = match n {
+            1 => format!("{base}.md"),
+            n => format!("{base}-{n}.md"),
+        };
+        let path = dir.join(name);
+        match create_private(&path) {
+            Ok(file) => break (path, file),
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => n += 1,
+            Err(e) => return Err(e).with_context(|| format!("create {}", path.display())),
+        }
+    };
+    file.write_all(to_markdown(agent_id, records, now).as_bytes())
+        .with_context(|| format!("write {}", path.display()))?;
+    Ok(path)
+}
+
+/// Create `path`, failing if it exists; mode 0600 on Unix.
+fn create_private(path: &Path) -> std::io::Result<File> {
+    let mut options = OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    options.open(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn markdown_has_sections_and_safe_fences() {
+        let records = [
+            TranscriptRecord::User {
+                text: "fix the build".into(),
+                timestamp: Some("10:00".into()),
+            },
+            TranscriptRecord::ToolUse {
+                tool_name: "Bash".into(),
+                input_summary: "cargo build".into(),
+                input_full: None,
+                timestamp: None,
+            },
+            TranscriptRecord::ToolResult {
+                output_summary: "error: see ```code```".into(),
+                is_error: true,
+                timestamp: None,
+            },
+            TranscriptRecord::AssistantText {
+                text: "Fixed.".into(),
+                timestamp: None,
+            },
+        ];
+        let now = Local.with_ymd_and_hms(2026, 5, 12, 10, 30, 0).unwrap();
+        let md = to_markdown("main:0.0", &records, now);
+        assert!(md.starts_with("# Transcript — main:0.0\n"));
+        assert!(md.contains("\n## User · 10:00\n\nfix the build\n"));
+        assert!(md.contains("\n## Tool: Bash\n\n`cargo build`\n"));
+        assert!(md.contains("**Error:**\n\n````\nerror: see ```code```\n````\n"));
+        assert!(md.ends_with("\n## Assistant\n\nFixed.\n"));
+    }
+
+    #[test]
+    fn write_uses_a_sanitized_file_name() {
+        let dir = std::env::temp_dir().join(format!("tmai-export-{}", std::process::id()));
+        let path = write_markdown(&dir, "main:0.0/x", &[]).unwrap();
+        let name = path.file_name().unwrap().to_string_lossy().into_owned();
+        assert!(
+            name.starts_with("main_0.0_x-") && name.ends_with(".md"),
+            "{name}"
+        );
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn exports_in_the_same_second_get_their_own_files() {
+        let dir = std::env::temp_dir().join(format!("tmai-export-twice-{}", std::process::id()));
+        let first = write_markdown(&dir, "a", &[]).unwrap();
+        let second = write_markdown(&dir, "a", &[]).unwrap();
+        assert_ne!(first, second);
+        assert!(first.exists() && second.exists());
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(&first).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,338 @@
+//! User commands run when an agent changes attention state.
+//!
+//! tmai-core's event stream only carries full agent snapshots to this
+//! client, so hooks fire on the transitions visible here: an agent
+//! becoming halted, started or completed. Each command runs under `sh -c`
+//! with a JSON payload on stdin, e.g.
+//! `{"event":"halted","agent_id":"main:0.1",...}`. Commands are limited
+//! to [`HooksConfig::max_concurrent`] at a time (extra invocations are
+//! dropped, not queued) and killed after [`HooksConfig::timeout_secs`].
+//! On Unix each hook runs in its own process group and the whole group is
+//! killed on timeout, so children the command started go too. Every
+//! invocation and its outcome is logged.
+
+use std::collections::HashMap;
+use std::process::Stdio;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use anyhow::{bail, Result};
+use serde::Deserialize;
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+use tokio::sync::Semaphore;
+
+use crate::types::{AgentAttention, AgentSnapshot};
+
+/// `[hooks]` table in `ratatui.toml`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct HooksConfig {
+    pub on_halted: Option<String>,
+    pub on_started: Option<String>,
+    pub on_completed: Option<String>,
+    /// Seconds before a running hook is killed.
+    pub timeout_secs: u64,
+    /// Hooks allowed to run at once.
+    pub max_concurrent: usize,
+}
+
+impl Default for HooksConfig {
+    fn default() -> Self {
+        Self {
+            on_halted: None,
+            on_started: None,
+            on_completed: None,
+            timeout_secs: 10,
+            max_concurrent: 4,
+        }
+    }
+}
+
+impl HooksConfig {
+    /// Reject limits that would stop every hook: no slots, or killed at
+    /// once.
+    pub fn validate(&self) -> Result<()> {
+        if self.max_concurrent == 0 {
+            bail!("hooks.max_concurrent must be at least 1");
+        }
+        if self.timeout_secs == 0 {
+            bail!("hooks.timeout_secs must be at least 1");
+        }
+        Ok(())
+    }
+}
+
+/// A transition worth running a hook for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HookEvent {
+    Halted,
+    Started,
+    Completed,
+}
+
+impl HookEvent {
+    fn of(attention: Option<&AgentAttention>) -> Option<Self> {
+        match attention? {
+            AgentAttention::halted => Some(Self::Halted),
+            AgentAttention::started => Some(Self::Started),
+            AgentAttention::completed => Some(Self::Completed),
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Halted => "halted",
+            Self::Started => "started",
+            Self::Completed => "completed",
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Hooks {
+    config: HooksConfig,
+    slots: Arc<Semaphore>,
+    /// Last seen event state per agent id (`None` = running).
+    last: HashMap<String, Option<HookEvent>>,
+    /// Agents in the first snapshot are not transitions.
+    primed: bool,
+}
+
+impl Hooks {
+    pub fn new(config: HooksConfig) -> Self {
+        let slots = Arc::new(Semaphore::new(config.max_concurrent));
+        Self {
+            config,
+            slots,
+            last: HashMap::new(),
+            primed: false,
+        }
+    }
+
+    /// Fold a new snapshot in and return the agents that just entered a
+    /// hookable state. Agents that disappeared are forgotten.
+    pub fn observe<'a>(
+        &mut self,
+        agents: &'a [AgentSnapshot],
+    ) -> Vec<(HookEvent, &'a AgentSnapshot)> {
+        let mut fired = Vec::new();
+        let mut next = HashMap::with_capacity(agents.len());
+        for agent in agents {
+            let state = HookEvent::of(agent.attention.as_ref());
+            let changed = match self.last.get(&agent.id) {
+                Some(prev) => *prev != state,
+                None => self.primed,
+            };
+            if let (true, Some(event)) = (changed, state) {
+                fired.push((event, agent));
+            }
+            next.insert(agent.id.clone(), state);
+        }
+        self.last = next;
+        self.primed = true;
+        fired
+    }
+
+    fn command(&self, event: HookEvent) -> Option<&str> {
+        match event {
+            HookEvent::Halted => self.config.on_halted.as_deref(),
+            HookEvent::Started => self.config.on_started.as_deref(),
+            HookEvent::Completed => self.config.on_completed.as_deref(),
+        }
+    }
+
+    /// Observe `agents` and spawn the configured command for each
+    /// transition. Must be called from within the tokio runtime.
+    pub fn dispatch(&mut self, agents: &[AgentSnapshot]) {
+        for (event, agent) in self.observe(agents) {
+            let Some(command) = self.command(event) else {
+                continue;
+            };
+            let Ok(permit) = self.slots.clone().try_acquire_owned() else {
+                tracing::warn!(
+                    "hook {} for {}: {} already running, skipped",
+                    event.name(),
+                    agent.id,
+                    self.config.max_concurrent
+                );
+                continue;
+            };
+            let command = command.to_string();
+            let payload = payload(event, agent);
+            let id = agent.id.clone();
+            let timeout = Duration::from_secs(self.config.timeout_secs);
+            tokio::spawn(async move {
+                let started = Instant::now();
+                let outcome = run(&command, &payload, timeout).await;
+                let ms = started.elapsed().as_millis();
+                match outcome {
+                    Ok(()) => tracing::info!("hook {} for {id}: ok in {ms}ms", event.name()),
+                    Err(e) => tracing::warn!("hook {} for {id}: {e} after {ms}ms", event.name()),
+                }
+                drop(permit);
+            });
+        }
+    }
+}
+
+/// JSON written to the hook's stdin.
+pub fn payload(event: HookEvent, agent: &AgentSnapshot) -> String {
+    serde_json::json!({
+        "event": event.name(),
+        "agent_id": agent.id,
+        "target": agent.target,
+        "display_label": agent.display_label,
+        "cwd": agent.cwd,
+        "git_branch": agent.git_branch,
+        "unit": agent.unit,
+    })
+    .to_string()
+}
+
+async fn run(command: &str, payload: &str, timeout: Duration) -> Result<(), String> {
+    let mut cmd = Command::new("sh");
+    cmd.arg("-c")
+        .arg(command)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
+    #[cfg(unix)]
+    cmd.process_group(0);
+    let mut child = cmd.spawn().map_err(|e| format!("spawn: {e}"))?;
+    let pid = child.id();
+    if let Some(mut stdin) = child.stdin.take() {
+        // A hook that ignores stdin may exit before reading it.
+        let _ = stdin.write_all(payload.as_bytes()).await;
+    }
+    let output = match tokio::time::timeout(timeout, child.wait_with_output()).await {
+        Ok(output) => output.map_err(|e| format!("wait: {e}"))?,
+        Err(_) => {
+            // kill_on_drop only reaches `sh`; take its children with it.
+            if let Some(pid) = pid {
+                kill_group(pid);
+            }
+            return Err(format!("timed out after {}s, killed", timeout.as_secs()));
+        }
+    };
+    if output.status.success() {
+        Ok(())
+    } else {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        Err(format!("{} {}", output.status, stderr.trim()))
+    }
+}
+
+/// SIGKILL the process group led by `pid`.
+#[cfg(unix)]
+fn kill_group(pid: u32) {
+    // SAFETY: killpg only sends a signal; the group is the one `run`
+    // created for the hook, so nothing outside it is hit.
+    unsafe {
+        libc::killpg(pid as libc::pid_t, libc::SIGKILL);
+    }
+}
+
+#[cfg(not(unix))]
+fn kill_group(_pid: u32) {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::test_support::agent;
+
+    fn ids(fired: Vec<(HookEvent, &AgentSnapshot)>) -> Vec<(HookEvent, String)> {
+        fired.into_iter().map(|(e, a)| (e, a.id.clone())).collect()
+    }
+
+    #[test]
+    fn fires_on_transitions_only() {
+        let mut hooks = Hooks::new(HooksConfig::default());
+        // First snapshot primes without firing.
+        assert!(hooks.observe(&[agent("a", Some("halted"))]).is_empty());
+        // Unchanged state doesn't refire; a new agent in a state does.
+        let snapshot = [agent("a", Some("halted")), agent("b", Some("started"))];
+        assert_eq!(
+            ids(hooks.observe(&snapshot)),
+            [(HookEvent::Started, "b".to_string())]
+        );
+        // Going back to running fires nothing; completing does.
+        assert!(hooks.observe(&[agent("a", None)]).is_empty());
+        let snapshot = [agent("a", Some("completed"))];
+        assert_eq!(
+            ids(hooks.observe(&snapshot)),
+            [(HookEvent::Completed, "a".to_string())]
+        );
+    }
+
+    #[test]
+    fn payload_names_event_and_agent() {
+        let v: serde_json::Value =
+            serde_json::from_str(&payload(HookEvent::Halted, &agent("main:0.1", None))).unwrap();
+        assert_eq!(v["event"], "halted");
+        assert_eq!(v["agent_id"], "main:0.1");
+    }
+
+    #[tokio::test]
+    async fn run_reports_failure_and_timeout() {
+        assert!(run("cat >/dev/null", "{}", Duration::from_secs(5))
+            .await
+            .is_ok());
+        let err = run("echo boom >&2; exit 3", "{}", Duration::from_secs(5))
+            .await
+            .unwrap_err();
+        assert!(err.contains("boom"), "{err}");
+        let err = run("sleep 5", "{}", Duration::from_millis(50))
+            .await
+            .unwrap_err();
+        assert!(err.contains("timed out"), "{err}");
+    }
+
+    #[test]
+    fn zero_limits_are_rejected() {
+        HooksConfig::default().validate().unwrap();
+        for config in [
+            HooksConfig {
+                max_concurrent: 0,
+                ..HooksConfig::default()
+            },
+            HooksConfig {
+                timeout_secs: 0,
+                ..HooksConfig::default()
+            },
+        ] {
+            assert!(config.validate().is_err(), "{config:?}");
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    #[tokio::test]
+    async fn timeout_kills_the_hooks_children() {
+        let pid_file = std::env::temp_dir().join(format!("tmai-hook-child-{}", std::process::id()));
+        let command = format!("sleep 30 & echo $! > {}; wait", pid_file.display());
+        let err = run(&command, "{}", Duration::from_millis(200))
+            .await
+            .unwrap_err();
+        assert!(err.contains("timed out"), "{err}");
+
+        let pid = std::fs::read_to_string(&pid_file).unwrap();
+        std::fs::remove_file(&pid_file).unwrap();
+        let stat = format!("/proc/{}/stat", pid.trim());
+        // Dead once its /proc entry is gone or it is a zombie awaiting reaping.
+        let alive = || {
+            std::fs::read_to_string(&stat).is_ok_and(|s| {
+                s.rsplit(')')
+                    .next()
+                    .is_some_and(|r| !r.trim_start().starts_with('Z'))
+            })
+        };
+        for _ in 0..50 {
+            if !alive() {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        panic!("background child {} outlived the hook", pid.trim());
+    }
+}
@@ -1,4 +1,12 @@
 pub mod api;
+pub mod config;
+pub mod doctor;
 pub mod events;
+pub mod export;
+pub mod hooks;
+pub mod logbuf;
+pub mod pins;
+pub mod prompt_history;
+pub mod redact;
 pub mod types;
 pub mod ui;
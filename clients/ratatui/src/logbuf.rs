@@ -0,0 +1,227 @@
+//! In-memory ring buffer of this client's own log.
+//!
+//! `--debug` writes a log file, but reading it means a second terminal and
+//! remembering the flag before the problem happened. [`LogBuffer`] is a
+//! `tracing` layer that is always installed and keeps the last
+//! [`CAPACITY`] events for the log overlay.
+//!
+//! [`LevelControl`] swaps the capture filter at runtime, so a flaky
+//! problem can be looked at in `debug` or `trace` without restarting the
+//! client and losing the repro.
+
+use std::collections::VecDeque;
+use std::fmt::Write as _;
+use std::sync::{Arc, Mutex};
+
+use anyhow::{Context as _, Result};
+use chrono::{DateTime, Local};
+use tracing::field::{Field, Visit};
+use tracing::{Event, Level, Subscriber};
+use tracing_subscriber::layer::{Context, Layer};
+use tracing_subscriber::{reload, EnvFilter, Registry};
+
+/// Events kept; older ones are dropped.
+pub const CAPACITY: usize = 2000;
+
+#[derive(Debug, Clone)]
+pub struct LogLine {
+    pub at: DateTime<Local>,
+    pub level: Level,
+    pub target: String,
+    pub message: String,
+}
+
+/// Cheap to clone; clones share the buffer.
+#[derive(Debug, Clone)]
+pub struct LogBuffer {
+    lines: Arc<Mutex<VecDeque<LogLine>>>,
+    capacity: usize,
+}
+
+impl Default for LogBuffer {
+    fn default() -> Self {
+        Self::with_capacity(CAPACITY)
+    }
+}
+
+impl LogBuffer {
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            lines: Arc::new(Mutex::new(VecDeque::with_capacity(capacity))),
+            capacity,
+        }
+    }
+
+    pub fn push(&self, line: LogLine) {
+        let mut lines = self.lines.lock().expect("log buffer poisoned");
+        if lines.len() == self.capacity {
+            lines.pop_front();
+        }
+        lines.push_back(line);
+    }
+
+    /// Copy of the buffered lines, oldest first.
+    pub fn snapshot(&self) -> Vec<LogLine> {
+        self.lines
+            .lock()
+            .expect("log buffer poisoned")
+            .iter()
+            .cloned()
+            .collect()
+    }
+}
+
+/// Collects an event's `message` plus any other fields as `key=value`.
+#[derive(Default)]
+struct MessageVisitor {
+    message: String,
+    fields: String,
+}
+
+impl Visit for MessageVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.message.push_str(value);
+        } else {
+            let _ = write!(self.fields, " {}={value}", field.name());
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        if field.name() == "message" {
+            let _ = write!(self.message, "{value:?}");
+        } else {
+            let _ = write!(self.fields, " {}={value:?}", field.name());
+        }
+    }
+}
+
+impl<S: Subscriber> Layer<S> for LogBuffer {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let mut visitor = MessageVisitor::default();
+        event.record(&mut visitor);
+        let meta = event.metadata();
+        self.push(LogLine {
+            at: Local::now(),
+            level: *meta.level(),
+            target: meta.target().to_string(),
+            message: visitor.message + &visitor.fields,
+        });
+    }
+}
+
+/// Crate whose level [`LevelControl`] cycles; other targets stay at the
+/// base filter.
+const OWN_TARGET: &str = "tmai_ratatui";
+
+/// Runtime control over the global log filter.
+pub struct LevelControl {
+    /// `None` when logging wasn't set up through [`LevelControl::install`].
+    handle: Option<reload::Handle<EnvFilter, Registry>>,
+    /// `RUST_LOG`, or `info`.
+    base: String,
+    /// Level set for this crate by the last [`LevelControl::cycle`].
+    level: Option<Level>,
+}
+
+impl LevelControl {
+    /// Reloadable filter layer from `RUST_LOG` (default `info`), plus the
+    /// control for it.
+    pub fn install() -> (reload::Layer<EnvFilter, Registry>, Self) {
+        let base = std::env::var("RUST_LOG")
+            .ok()
+            .filter(|v| EnvFilter::try_new(v).is_ok())
+            .unwrap_or_else(|| "info".into());
+        let (layer, handle) = reload::Layer::new(EnvFilter::new(&base));
+        let control = Self {
+            handle: Some(handle),
+            base,
+            level: None,
+        };
+        (layer, control)
+    }
+
+    /// A control that changes nothing, for when logging isn't installed.
+    pub fn detached() -> Self {
+        Self {
+            handle: None,
+            base: "info".into(),
+            level: None,
+        }
+    }
+
+    /// Current directive string, e.g. `info,tmai_ratatui=debug`.
+    pub fn directives(&self) -> String {
+        match self.level {
+            Some(level) => format!(
+                "{},{OWN_TARGET}={}",
+                self.base,
+                level.as_str().to_lowercase()
+            ),
+            None => self.base.clone(),
+        }
+    }
+
+    /// Step this crate's level info → debug → trace → info and apply it.
+    pub fn cycle(&mut self) -> Result<String> {
+        self.level = Some(match self.level {
+            None | Some(Level::INFO) => Level::DEBUG,
+            Some(Level::DEBUG) => Level::TRACE,
+            Some(_) => Level::INFO,
+        });
+        let directives = self.directives();
+        if let Some(handle) = &self.handle {
+            handle
+                .reload(EnvFilter::new(&directives))
+                .context("reload log filter")?;
+        }
+        Ok(directives)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tracing_subscriber::layer::SubscriberExt;
+
+    #[test]
+    fn captures_events_and_drops_oldest() {
+        let buf = LogBuffer::with_capacity(2);
+        let subscriber = tracing_subscriber::registry().with(buf.clone());
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::info!("first");
+            tracing::warn!(agent = "main:0.1", "second");
+            tracing::debug!("third {}", 3);
+        });
+        let lines = buf.snapshot();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0].level, Level::WARN);
+        assert_eq!(lines[0].message, "second agent=main:0.1");
+        assert_eq!(lines[1].message, "third 3");
+    }
+
+    #[test]
+    fn cycle_raises_only_own_target() {
+        let mut control = LevelControl::detached();
+        assert_eq!(control.directives(), "info");
+        assert_eq!(control.cycle().unwrap(), "info,tmai_ratatui=debug");
+        assert_eq!(control.cycle().unwrap(), "info,tmai_ratatui=trace");
+        assert_eq!(control.cycle().unwrap(), "info,tmai_ratatui=info");
+    }
+
+    #[test]
+    fn reload_changes_what_is_captured() {
+        let buf = LogBuffer::with_capacity(10);
+        let (filter, mut control) = LevelControl::install();
+        let subscriber = tracing_subscriber::registry()
+            .with(filter)
+            .with(buf.clone());
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::debug!("hidden");
+            control.cycle().unwrap();
+            tracing::debug!("shown");
+        });
+        let messages: Vec<_> = buf.snapshot().into_iter().map(|l| l.message).collect();
+        assert_eq!(messages, ["shown"]);
+    }
+}
@@ -1,8 +1,13 @@
+use std::path::PathBuf;
+
 use anyhow::{bail, Context, Result};
-use clap::Parser;
-use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, EnvFilter};
+use clap::{Parser, Subcommand};
+use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
 use tmai_ratatui::api::{self, ApiClient};
+use tmai_ratatui::config::Config;
+use tmai_ratatui::doctor;
+use tmai_ratatui::logbuf::{LevelControl, LogBuffer};
 
 #[derive(Debug, Parser)]
 #[command(
@@ -21,15 +26,36 @@ struct Cli {
     #[arg(long)]
     token: Option<String>,
 
-    /// Write verbose logs to `tmai-ratatui.log` in the current directory.
+    /// Client config file. Defaults to `$XDG_CONFIG_HOME/tmai/ratatui.toml`
+    /// (a missing default file is fine; a missing explicit one is not).
+    #[arg(long)]
+    config: Option<PathBuf>,
+
+    /// Also write logs to `tmai-ratatui.log` in the current directory.
+    /// Recent log lines are always available in the log overlay (`L`).
     #[arg(long)]
     debug: bool,
+
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Check config, discovery, the tmai-core connection and local
+    /// directories, and suggest fixes for whatever fails.
+    Doctor,
 }
 
 #[tokio::main]
 async fn main() -> Result<()> {
     let cli = Cli::parse();
-    setup_logging(cli.debug)?;
+    if let Some(Command::Doctor) = cli.command {
+        return run_doctor(cli).await;
+    }
+    let logs = LogBuffer::default();
+    let level = setup_logging(cli.debug, logs.clone())?;
+    let config = Config::load(cli.config.as_deref()).context("load client config")?;
 
     let (base, token) = match (cli.url, cli.token) {
         (Some(url), Some(tok)) => (url, tok),
@@ -60,27 +86,46 @@ async fn main() -> Result<()> {
     }
 
     let client = ApiClient::new(base, token);
-    tmai_ratatui::ui::run(client).await
+    tmai_ratatui::ui::run(client, config, logs, level).await
 }
 
-fn setup_logging(debug: bool) -> Result<()> {
-    if !debug {
-        return Ok(());
+async fn run_doctor(cli: Cli) -> Result<()> {
+    let target = match (cli.url, cli.token) {
+        (Some(url), Some(tok)) => doctor::Target::Explicit(ApiClient::new(url, tok)),
+        (None, Some(_)) => bail!("--token requires --url"),
+        (url, None) => doctor::Target::Discover { url },
+    };
+    let checks = doctor::run(cli.config.as_deref(), target).await;
+    for check in &checks {
+        println!("{}", check.render());
     }
-    let file = std::fs::OpenOptions::new()
-        .create(true)
-        .append(true)
-        .open("tmai-ratatui.log")
-        .context("open log file")?;
-    let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info"));
-    tracing_subscriber::registry()
-        .with(filter)
-        .with(
+    if doctor::failed(&checks) {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+fn setup_logging(debug: bool, logs: LogBuffer) -> Result<LevelControl> {
+    let (filter, level) = LevelControl::install();
+    let file_layer = if debug {
+        let file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open("tmai-ratatui.log")
+            .context("open log file")?;
+        Some(
             tracing_subscriber::fmt::layer()
                 .with_writer(file)
                 .with_ansi(false),
         )
+    } else {
+        None
+    };
+    tracing_subscriber::registry()
+        .with(filter)
+        .with(logs)
+        .with(file_layer)
         .try_init()
         .ok();
-    Ok(())
+    Ok(level)
 }
@@ -0,0 +1,107 @@
+//! Pinned agents, persisted across client restarts.
+//!
+//! Pins are a per-user view preference, so they live with this client's
+//! own state (`$XDG_STATE_HOME/tmai/ratatui-pins.json`) rather than in
+//! tmai-core. Agents are keyed by id; a pin on an agent that no longer
+//! exists is harmless and is kept in case the agent comes back.
+
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+
+#[derive(Debug, Clone, Default)]
+pub struct Pins {
+    ids: BTreeSet<String>,
+    /// Where to save; `None` keeps pins in memory only.
+    path: Option<PathBuf>,
+}
+
+/// Default location: `$XDG_STATE_HOME/tmai/ratatui-pins.json`, falling
+/// back to the data dir on platforms without a state dir.
+pub fn default_path() -> Option<PathBuf> {
+    dirs::state_dir()
+        .or_else(dirs::data_dir)
+        .map(|d| d.join("tmai").join("ratatui-pins.json"))
+}
+
+impl Pins {
+    /// Load from `path`; a missing file is an empty set.
+    pub fn load(path: PathBuf) -> Result<Self> {
+        let ids = match std::fs::read_to_string(&path) {
+            Ok(raw) => {
+                serde_json::from_str(&raw).with_context(|| format!("parse {}", path.display()))?
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => BTreeSet::new(),
+            Err(e) => return Err(e).with_context(|| format!("read {}", path.display())),
+        };
+        Ok(Self {
+            ids,
+            path: Some(path),
+        })
+    }
+
+    pub fn contains(&self, id: &str) -> bool {
+        self.ids.contains(id)
+    }
+
+    /// Flip the pin on `id` and save. Returns whether it is now pinned.
+    /// When saving fails the pins are left as they were, so what is shown
+    /// matches what is on disk.
+    pub fn toggle(&mut self, id: &str) -> Result<bool> {
+        let mut ids = self.ids.clone();
+        let pinned = if ids.remove(id) {
+            false
+        } else {
+            ids.insert(id.to_string());
+            true
+        };
+        if let Some(path) = &self.path {
+            save(path, &ids)?;
+        }
+        self.ids = ids;
+        Ok(pinned)
+    }
+}
+
+fn save(path: &Path, ids: &BTreeSet<String>) -> Result<()> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).with_context(|| format!("create {}", dir.display()))?;
+    }
+    let body = serde_json::to_string_pretty(ids)?;
+    std::fs::write(path, body).with_context(|| format!("write {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn toggle_persists_across_loads() {
+        let dir = std::env::temp_dir().join(format!("tmai-pins-{}", std::process::id()));
+        let path = dir.join("pins.json");
+        let mut pins = Pins::load(path.clone()).unwrap();
+        assert!(!pins.contains("main:0.0"));
+        assert!(pins.toggle("main:0.0").unwrap());
+
+        let mut reloaded = Pins::load(path.clone()).unwrap();
+        assert!(reloaded.contains("main:0.0"));
+        assert!(!reloaded.toggle("main:0.0").unwrap());
+        assert!(!Pins::load(path).unwrap().contains("main:0.0"));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn failed_save_leaves_pins_unchanged() {
+        let blocker = std::env::temp_dir().join(format!("tmai-pins-file-{}", std::process::id()));
+        std::fs::write(&blocker, "").unwrap();
+        // The parent "directory" is a regular file, so saving fails.
+        let mut pins = Pins {
+            ids: BTreeSet::new(),
+            path: Some(blocker.join("pins.json")),
+        };
+        assert!(pins.toggle("main:0.0").is_err());
+        assert!(!pins.contains("main:0.0"));
+        std::fs::remove_file(&blocker).unwrap();
+    }
+}
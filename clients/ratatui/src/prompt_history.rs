@@ -0,0 +1,108 @@
+//! Prompts sent from this client, persisted across restarts for recall in
+//! the input box.
+//!
+//! Stored with the pins in this client's own state
+//! (`$XDG_STATE_HOME/tmai/ratatui-prompts.json`), newest last, capped at
+//! [`CAPACITY`]. Sending the same prompt twice in a row records it once.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+
+/// Prompts kept; older ones are dropped.
+pub const CAPACITY: usize = 500;
+
+#[derive(Debug, Clone, Default)]
+pub struct PromptHistory {
+    /// Oldest first.
+    entries: Vec<String>,
+    /// Where to save; `None` keeps history in memory only.
+    path: Option<PathBuf>,
+}
+
+/// Default location: `$XDG_STATE_HOME/tmai/ratatui-prompts.json`, falling
+/// back to the data dir on platforms without a state dir.
+pub fn default_path() -> Option<PathBuf> {
+    dirs::state_dir()
+        .or_else(dirs::data_dir)
+        .map(|d| d.join("tmai").join("ratatui-prompts.json"))
+}
+
+impl PromptHistory {
+    /// Load from `path`; a missing file is an empty history.
+    pub fn load(path: PathBuf) -> Result<Self> {
+        let entries = match std::fs::read_to_string(&path) {
+            Ok(raw) => {
+                serde_json::from_str(&raw).with_context(|| format!("parse {}", path.display()))?
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+            Err(e) => return Err(e).with_context(|| format!("read {}", path.display())),
+        };
+        Ok(Self {
+            entries,
+            path: Some(path),
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// The `n`th most recent prompt; 0 is the newest.
+    pub fn recent(&self, n: usize) -> Option<&str> {
+        self.entries.iter().rev().nth(n).map(String::as_str)
+    }
+
+    /// Record a sent prompt and save. Blank prompts and repeats of the
+    /// newest entry are ignored.
+    pub fn push(&mut self, prompt: &str) -> Result<()> {
+        if prompt.trim().is_empty() || self.recent(0) == Some(prompt) {
+            return Ok(());
+        }
+        self.entries.push(prompt.to_string());
+        if self.entries.len() > CAPACITY {
+            let excess = self.entries.len() - CAPACITY;
+            self.entries.drain(..excess);
+        }
+        match &self.path {
+            Some(path) => save(path, &self.entries),
+            None => Ok(()),
+        }
+    }
+}
+
+fn save(path: &Path, entries: &[String]) -> Result<()> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).with_context(|| format!("create {}", dir.display()))?;
+    }
+    let body = serde_json::to_string_pretty(entries)?;
+    std::fs::write(path, body).with_context(|| format!("write {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn push_persists_and_skips_repeats() {
+        let dir = std::env::temp_dir().join(format!("tmai-prompts-{}", std::process::id()));
+        let path = dir.join("prompts.json");
+        let mut history = PromptHistory::load(path.clone()).unwrap();
+        assert!(history.is_empty());
+        history.push("fix the tests").unwrap();
+        history.push("fix the tests").unwrap();
+        history.push("  ").unwrap();
+        history.push("line one\nline two").unwrap();
+
+        let reloaded = PromptHistory::load(path).unwrap();
+        assert_eq!(reloaded.len(), 2);
+        assert_eq!(reloaded.recent(0), Some("line one\nline two"));
+        assert_eq!(reloaded.recent(1), Some("fix the tests"));
+        assert_eq!(reloaded.recent(2), None);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
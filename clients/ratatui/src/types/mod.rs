@@ -1,11 +1,15 @@
 #[allow(nonstandard_style)]
 pub mod generated;
+#[cfg(test)]
+pub mod test_support;
 
 pub use generated::{
     AgentAttention, DispatchSnapshot, QueueSnapshot, RuntimeSnapshot, WorkflowSnapshot,
     WorktreeSnapshot,
 };
 
+use std::collections::HashMap;
+
 use serde::Deserialize;
 
 /// Agent snapshot returned by `GET /api/agents` and the `agents` SSE event.
@@ -31,6 +35,34 @@ pub struct AgentSnapshot {
     /// `None` / absent = running normally; no UI pill.
     #[serde(default)]
     pub attention: Option<AgentAttention>,
+    /// Working directory of the agent's pane; the repo path for `/git/*`.
+    #[serde(default)]
+    pub cwd: String,
+    #[serde(default)]
+    pub git_branch: Option<String>,
+    #[serde(default)]
+    pub git_dirty: Option<bool>,
+    /// Shared `.git` dir; equal across worktrees of one repository.
+    #[serde(default)]
+    pub git_common_dir: Option<String>,
+    /// tmai-core unit (`[[unit]]`); absent on engines that predate units.
+    #[serde(default)]
+    pub unit: Option<String>,
+    /// Context-window usage; absent until the agent has reported one.
+    #[serde(default)]
+    pub ctx_usage: Option<CtxUsage>,
+}
+
+/// Context-window usage from Claude Code's statusline hook
+/// (`AgentSnapshot.ctx_usage`).
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+pub struct CtxUsage {
+    /// Approximate tokens in the context window.
+    pub used: u64,
+    /// Window size in tokens.
+    pub total: u64,
+    /// Share of the window used, 0 to 100. Authoritative over `used`.
+    pub pct: f64,
 }
 
 /// Map an [`AgentAttention`] reading to a single-word label matching the
@@ -47,18 +79,211 @@ pub fn attention_label(a: Option<&AgentAttention>) -> &'static str {
     }
 }
 
+/// One entry of `GET /api/agents/{id}/transcript` — the agent's
+/// conversation as parsed by tmai-core from the vendor session log.
+/// Record kinds this client doesn't know decode as [`Self::Unknown`].
+#[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TranscriptRecord {
+    User {
+        text: String,
+        #[serde(default)]
+        timestamp: Option<String>,
+    },
+    AssistantText {
+        text: String,
+        #[serde(default)]
+        timestamp: Option<String>,
+    },
+    Thinking {
+        text: String,
+        #[serde(default)]
+        timestamp: Option<String>,
+    },
+    ToolUse {
+        tool_name: String,
+        #[serde(default)]
+        input_summary: String,
+        /// Raw tool input (e.g. `file_path` / `old_string` / `new_string`
+        /// for `Edit`). Absent on older tmai-core builds.
+        #[serde(default)]
+        input_full: Option<serde_json::Value>,
+        #[serde(default)]
+        timestamp: Option<String>,
+    },
+    ToolResult {
+        #[serde(default)]
+        output_summary: String,
+        #[serde(default)]
+        is_error: bool,
+        #[serde(default)]
+        timestamp: Option<String>,
+    },
+    #[serde(other)]
+    Unknown,
+}
+
+/// Body of `GET /api/agents/{id}/transcript`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct TranscriptResponse {
+    #[serde(default)]
+    pub records: Vec<TranscriptRecord>,
+}
+
+/// Per-vendor availability as tmai-core tracks it (`VendorAvailabilityState`).
+///
+/// Hand-written: the generated enum is a serde-less placeholder for the
+/// TS union, so it cannot decode the wire shape. States this client
+/// doesn't know decode as [`Self::Unknown`].
+#[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
+#[serde(tag = "state", rename_all = "snake_case")]
+pub enum VendorAvailability {
+    Available,
+    RateLimited {
+        /// RFC 3339 time the vendor is expected to accept requests again.
+        #[serde(default)]
+        resume_at: Option<String>,
+    },
+    Unavailable {
+        #[serde(default)]
+        reason: String,
+    },
+    #[serde(other)]
+    Unknown,
+}
+
+/// One entry of `RuntimeSnapshot.vendors`.
+#[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
+pub struct VendorStatus {
+    pub vendor: String,
+    pub state: VendorAvailability,
+    /// Account label when the deployment distinguishes accounts.
+    #[serde(default)]
+    pub account: Option<String>,
+    #[serde(default)]
+    pub last_changed_at: String,
+}
+
+/// Client view of `RuntimeSnapshot`, decodable unlike the generated one.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct RuntimeState {
+    #[serde(default)]
+    pub vendors: Vec<VendorStatus>,
+}
+
+/// `RuntimeUpdate` SSE envelope. Only the snapshot is read; `seq` /
+/// `origin` bookkeeping is left to clients that do delta replay.
+#[derive(Debug, Clone, Deserialize)]
+pub struct RuntimeUpdate {
+    #[serde(default)]
+    pub snapshot: Option<RuntimeState>,
+}
+
+/// The slice of `GET /api/bootstrap` this client reads.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct BootstrapResponse {
+    #[serde(default)]
+    pub snapshots: BootstrapSnapshots,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct BootstrapSnapshots {
+    #[serde(default)]
+    pub runtime: Option<RuntimeState>,
+}
+
+/// Upstream tracking for one local branch.
+#[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
+pub struct RemoteTracking {
+    pub remote_branch: String,
+    #[serde(default)]
+    pub ahead: u64,
+    #[serde(default)]
+    pub behind: u64,
+}
+
+/// The slice of `GET /api/git/branches?repo=` this client reads.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct BranchListResponse {
+    #[serde(default)]
+    pub default_branch: String,
+    /// `branch -> (ahead, behind)` relative to its parent branch.
+    #[serde(default)]
+    pub ahead_behind: HashMap<String, (u64, u64)>,
+    #[serde(default)]
+    pub parents: HashMap<String, String>,
+    #[serde(default)]
+    pub remote_tracking: HashMap<String, RemoteTracking>,
+}
+
 /// Payload for `POST /api/agents/{id}/input`.
 #[derive(Debug, serde::Serialize)]
 pub struct TextInputRequest<'a> {
     pub text: &'a str,
 }
 
+/// Payload for `POST /api/agents/{id}/prompt`.
+#[derive(Debug, serde::Serialize)]
+pub struct PromptRequest<'a> {
+    pub prompt: &'a str,
+}
+
+/// Response of `POST /api/agents/{id}/prompt`. tmai-core delivers the
+/// prompt right away when the agent is idle and queues it otherwise.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct PromptResponse {
+    pub status: String,
+    pub action: String,
+    pub queue_size: usize,
+}
+
+impl PromptResponse {
+    /// The prompt waits in tmai-core's queue rather than being typed in.
+    pub fn queued(&self) -> bool {
+        self.action == "queued" || self.queue_size > 0
+    }
+}
+
 /// Payload for `POST /api/agents/{id}/key`.
 #[derive(Debug, serde::Serialize)]
 pub struct KeyRequest<'a> {
     pub key: &'a str,
 }
 
+/// Payload for `POST /api/spawn/worktree`: create a worktree + branch
+/// `name` off `cwd`'s repository and launch an agent in it.
+#[derive(Debug, serde::Serialize)]
+pub struct SpawnWorktreeRequest<'a> {
+    pub name: &'a str,
+    pub cwd: &'a str,
+}
+
+/// Payload for `POST /api/spawn`: launch `command args…` in a new PTY.
+#[derive(Debug, serde::Serialize)]
+pub struct SpawnRequest<'a> {
+    pub command: &'a str,
+    pub args: &'a [String],
+    pub cwd: &'a str,
+}
+
+/// Response of `POST /api/spawn`.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct SpawnResponse {
+    pub session_id: String,
+    pub pid: u32,
+    pub command: String,
+}
+
+/// Payload for `POST /api/worktrees/delete`.
+#[derive(Debug, serde::Serialize)]
+pub struct DeleteWorktreeRequest<'a> {
+    pub repo_path: &'a str,
+    pub worktree_name: &'a str,
+    pub force: bool,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -130,10 +355,69 @@ mod tests {
         assert!(matches!(a.attention, Some(AgentAttention::halted)));
     }
 
+    #[test]
+    fn transcript_records_decode_with_unknown_kinds() {
+        let json = r#"{"records":[
+            {"type":"user","text":"hi"},
+            {"type":"tool_use","tool_name":"Bash","input_summary":"ls","input_full":{"command":"ls"}},
+            {"type":"tool_result","output_summary":"boom","is_error":true},
+            {"type":"image","data":"..."}
+        ]}"#;
+        let r: TranscriptResponse = serde_json::from_str(json).unwrap();
+        assert_eq!(r.records.len(), 4);
+        assert!(
+            matches!(&r.records[1], TranscriptRecord::ToolUse { tool_name, .. } if tool_name == "Bash")
+        );
+        assert!(matches!(
+            r.records[2],
+            TranscriptRecord::ToolResult { is_error: true, .. }
+        ));
+        assert_eq!(r.records[3], TranscriptRecord::Unknown);
+    }
+
     #[test]
     fn attention_field_round_trips_with_started() {
         let json = r#"{"id":"x","target":"x","attention":"started"}"#;
         let a: AgentSnapshot = serde_json::from_str(json).unwrap();
         assert!(matches!(a.attention, Some(AgentAttention::started)));
     }
+
+    #[test]
+    fn vendor_availability_decodes_each_state() {
+        let json = r#"{"vendors":[
+            {"vendor":"claude","state":{"state":"rate_limited","resume_at":"2026-05-12T14:00:00Z"},"last_changed_at":"t"},
+            {"vendor":"codex","state":{"state":"available"},"last_changed_at":"t"},
+            {"vendor":"gemini","state":{"state":"unavailable","reason":"auth expired"},"last_changed_at":"t"},
+            {"vendor":"next","state":{"state":"degraded"},"last_changed_at":"t"}
+        ]}"#;
+        let r: RuntimeState = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            r.vendors[0].state,
+            VendorAvailability::RateLimited {
+                resume_at: Some("2026-05-12T14:00:00Z".into())
+            }
+        );
+        assert_eq!(r.vendors[1].state, VendorAvailability::Available);
+        assert!(matches!(
+            &r.vendors[2].state,
+            VendorAvailability::Unavailable { reason } if reason == "auth expired"
+        ));
+        assert_eq!(r.vendors[3].state, VendorAvailability::Unknown);
+    }
+
+    #[test]
+    fn branch_list_decodes_tuples_and_tracking() {
+        let json = r#"{
+            "default_branch": "main",
+            "current_branch": "feat",
+            "branches": ["main", "feat"],
+            "parents": {"feat": "main"},
+            "ahead_behind": {"feat": [3, 1]},
+            "remote_tracking": {"feat": {"remote_branch": "origin/feat", "ahead": 2, "behind": 0}},
+            "last_fetch": null
+        }"#;
+        let b: BranchListResponse = serde_json::from_str(json).unwrap();
+        assert_eq!(b.ahead_behind["feat"], (3, 1));
+        assert_eq!(b.remote_tracking["feat"].remote_branch, "origin/feat");
+    }
 }
@@ -0,0 +1,12 @@
+//! Fixtures shared by unit tests across the crate.
+
+use super::AgentSnapshot;
+
+/// A minimal agent: `target` equals `id`, everything else defaulted.
+/// `attention` is the wire string (`"halted"`, …) or `None` for running.
+pub fn agent(id: &str, attention: Option<&str>) -> AgentSnapshot {
+    serde_json::from_value(serde_json::json!({
+        "id": id, "target": id, "attention": attention,
+    }))
+    .unwrap()
+}
@@ -1,12 +1,16 @@
 //! Event loop: dispatches terminal input to the API client and SSE
 //! events to the list view.
 
+use std::collections::{BTreeSet, HashMap};
 use std::io::Stdout;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use anyhow::Result;
 use crossterm::{
-    event::{DisableMouseCapture, EnableMouseCapture, Event, EventStream, KeyCode, KeyModifiers},
+    event::{
+        DisableBracketedPaste, DisableMouseCapture, EnableBracketedPaste, EnableMouseCapture,
+        Event, EventStream, KeyCode, KeyModifiers,
+    },
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
@@ -15,32 +19,188 @@ use ratatui::{backend::CrosstermBackend, Terminal};
 use tokio::sync::mpsc;
 
 use crate::api::ApiClient;
+use crate::config::Config;
 use crate::events::{self, AppEvent};
-use crate::types::AgentSnapshot;
+use crate::export;
+use crate::hooks::Hooks;
+use crate::logbuf::{LevelControl, LogBuffer};
+use crate::pins::{self, Pins};
+use crate::prompt_history::{self, PromptHistory};
+use crate::redact::Redactor;
+use crate::types::{
+    attention_label, AgentAttention, AgentSnapshot, BranchListResponse, TranscriptRecord,
+    VendorStatus, WorktreeSnapshot,
+};
+use crate::ui::approvals::{self, ApprovalCheck};
+use crate::ui::attention;
+use crate::ui::choices::{self, ChoicesView};
+use crate::ui::diff::{self, DiffView};
+use crate::ui::durations::StateClock;
+use crate::ui::finish::{self, Finishing};
+use crate::ui::git_info::{self, GitInfoCache};
+use crate::ui::groups::{self, Group};
+use crate::ui::help;
+use crate::ui::history::{self, HistoryView};
+use crate::ui::input::{Edit, PromptInput};
+use crate::ui::keymap::{Action, ChordBuffer, KeyCombo, Keymap};
+use crate::ui::logs::{self, LogsView};
+use crate::ui::presets::{self, PendingPrompts, Preset, PresetsView};
 use crate::ui::session_list::{render, InputModeView, SessionListView};
+use crate::ui::settings::{self, SettingsView};
+use crate::ui::templates::{self, Template, TemplatesView};
+use crate::ui::worktrees::{self, WorktreePrompt, WorktreesView};
 
 #[derive(Debug, Clone)]
 pub enum InputMode {
     Normal,
-    SendText(String),
+    SendText(PromptInput),
+    /// Prompt for every agent in the selected agent's group.
+    Broadcast(PromptInput),
+    /// Feedback for a rejected approval.
+    Reject {
+        id: String,
+        input: PromptInput,
+    },
     ConfirmKill(String), // agent id
+    History(Box<HistoryView>),
+    Diff(Box<DiffView>),
+    Choices(Box<ChoicesView>),
+    Settings(Box<SettingsView>),
+    Worktrees(Box<WorktreesView>),
+    Presets(Box<PresetsView>),
+    /// Prompt templates, opened from the send-text input.
+    Templates(Box<TemplatesView>),
+    Logs(Box<LogsView>),
+    /// Every key binding; any key closes it.
+    Help,
 }
 
 struct AppState {
     agents: Vec<AgentSnapshot>,
+    /// Vendor availability from the runtime snapshot.
+    vendors: Vec<VendorStatus>,
     selected: usize,
     input_mode: InputMode,
     status_line: String,
+    keymap: Keymap,
+    redactor: Redactor,
+    /// Keys typed so far toward a multi-key chord (`g g`).
+    chord: ChordBuffer,
+    git: GitInfoCache,
+    /// Project groups of `agents` (which is kept sorted by project).
+    groups: Vec<Group>,
+    /// Fold every project except the selected agent's.
+    fold_projects: bool,
+    pins: Pins,
+    clock: StateClock,
+    /// Within each group, list the agent longest in its state first.
+    sort_by_duration: bool,
+    hooks: Hooks,
+    presets: Vec<Preset>,
+    /// `[[prompts]]` from the config; files are read when the list opens.
+    prompts: Vec<Template>,
+    /// Agents sent the wrap-up prompt, to be killed once they're done.
+    finishing: Finishing,
+    /// This client's own recent log, for the log overlay.
+    logs: LogBuffer,
+    log_level: LevelControl,
+    /// Approvals sent, until the agent is seen moving on.
+    approvals: ApprovalCheck,
+    /// Prompts sent from the input box, for recall.
+    prompt_history: PromptHistory,
+    /// Preset initial prompts waiting for their agent to appear.
+    initial_prompts: PendingPrompts,
 }
 
 impl AppState {
-    fn new() -> Self {
+    fn new(
+        config: Config,
+        pins: Pins,
+        prompt_history: PromptHistory,
+        logs: LogBuffer,
+        log_level: LevelControl,
+    ) -> Self {
         Self {
             agents: Vec::new(),
+            vendors: Vec::new(),
             selected: 0,
             input_mode: InputMode::Normal,
             status_line: "connecting…".into(),
+            keymap: config.keymap,
+            redactor: config.redactor,
+            chord: ChordBuffer::default(),
+            git: GitInfoCache::default(),
+            groups: Vec::new(),
+            fold_projects: false,
+            pins,
+            clock: StateClock::default(),
+            sort_by_duration: false,
+            hooks: Hooks::new(config.hooks),
+            presets: config.presets,
+            prompts: config.prompts,
+            finishing: Finishing::default(),
+            logs,
+            log_level,
+            approvals: ApprovalCheck::default(),
+            prompt_history,
+            initial_prompts: PendingPrompts::default(),
+        }
+    }
+
+    /// Install a fresh agent list, grouped by project, keeping the
+    /// selection on the same agent when it is still present.
+    fn set_agents(&mut self, agents: Vec<AgentSnapshot>) {
+        let selected_id = self.current().map(|a| a.id.clone());
+        self.clock.observe(&agents, Instant::now());
+        self.hooks.dispatch(&agents);
+        self.agents = agents;
+        self.regroup(selected_id);
+    }
+
+    /// Re-sort by project (after new data or a pin change) and move the
+    /// selection back onto `selected_id`.
+    fn regroup(&mut self, selected_id: Option<String>) {
+        groups::sort_by_project(&mut self.agents, &self.pins);
+        if self.sort_by_duration {
+            let now = Instant::now();
+            for g in groups::groups(&self.agents, &self.pins) {
+                self.agents[g.range].sort_by_key(|a| {
+                    std::cmp::Reverse(self.clock.elapsed(&a.id, now).map(|(d, _)| d))
+                });
+            }
+        }
+        if let Some(i) = selected_id.and_then(|id| self.agents.iter().position(|a| a.id == id)) {
+            self.selected = i;
         }
+        self.groups = groups::groups(&self.agents, &self.pins);
+        self.clamp();
+    }
+
+    /// Group index of the selected agent.
+    fn current_group(&self) -> Option<usize> {
+        self.groups
+            .iter()
+            .position(|g| g.range.contains(&self.selected))
+    }
+
+    /// Agents j/k cycle through: the selected agent's project when the
+    /// others are folded, otherwise everything.
+    fn nav_range(&self) -> std::ops::Range<usize> {
+        match self.current_group() {
+            Some(g) if self.fold_projects => self.groups[g].range.clone(),
+            _ => 0..self.agents.len(),
+        }
+    }
+
+    /// The selected agent's group and its members that take input.
+    fn broadcast_targets(&self) -> Option<(String, Vec<String>)> {
+        let group = &self.groups[self.current_group()?];
+        let ids = self.agents[group.range.clone()]
+            .iter()
+            .filter(|a| !a.is_virtual)
+            .map(|a| a.id.clone())
+            .collect();
+        Some((group.key.label().to_string(), ids))
     }
 
     fn clamp(&mut self) {
@@ -54,44 +214,148 @@ impl AppState {
     fn current(&self) -> Option<&AgentSnapshot> {
         self.agents.get(self.selected)
     }
+
+    /// Paste into the input box, if one is open.
+    fn paste(&mut self, text: &str) {
+        match &mut self.input_mode {
+            InputMode::SendText(input)
+            | InputMode::Broadcast(input)
+            | InputMode::Reject { input, .. } => input.paste(text),
+            _ => {}
+        }
+    }
+
+    /// Add a sent prompt to the recall history.
+    fn remember(&mut self, prompt: &str) {
+        if let Err(e) = self.prompt_history.push(prompt) {
+            tracing::warn!("prompt history: {e:#}");
+        }
+    }
 }
 
-pub async fn run(client: ApiClient) -> Result<()> {
-    let mut state = AppState::new();
+pub async fn run(
+    client: ApiClient,
+    config: Config,
+    logs: LogBuffer,
+    log_level: LevelControl,
+) -> Result<()> {
+    // Pins are a convenience; a broken file shouldn't keep the TUI from
+    // starting.
+    let pins = match pins::default_path().map(Pins::load) {
+        Some(Ok(pins)) => pins,
+        Some(Err(e)) => {
+            tracing::warn!("pins: {e:#}");
+            Pins::default()
+        }
+        None => Pins::default(),
+    };
+    let prompt_history = match prompt_history::default_path().map(PromptHistory::load) {
+        Some(Ok(history)) => history,
+        Some(Err(e)) => {
+            tracing::warn!("prompt history: {e:#}");
+            PromptHistory::default()
+        }
+        None => PromptHistory::default(),
+    };
+    let mut state = AppState::new(config, pins, prompt_history, logs, log_level);
 
     // Backfill initial snapshot.
     match events::backfill(&client).await {
         Ok(agents) => {
-            state.agents = agents;
+            state.set_agents(agents);
             state.status_line = format!("connected to {}", client.base_url());
         }
         Err(e) => {
             state.status_line = format!("backfill failed: {e}");
         }
     }
+    refresh_vendors(&client, &mut state).await;
 
     let (ev_tx, mut ev_rx) = mpsc::unbounded_channel::<AppEvent>();
-    events::spawn(client.clone(), ev_tx);
+    events::spawn(client.clone(), ev_tx.clone());
 
     let mut terminal = setup_terminal()?;
     let mut keys = EventStream::new();
 
-    let result = event_loop(&mut terminal, &client, &mut state, &mut keys, &mut ev_rx).await;
+    let result = event_loop(
+        &mut terminal,
+        &client,
+        &mut state,
+        &mut keys,
+        &ev_tx,
+        &mut ev_rx,
+    )
+    .await;
     teardown_terminal(&mut terminal)?;
     result
 }
 
+/// Start fetching `/git/branches` for the selected agent's repo, and the
+/// worktree list, when the cached copies have expired. The requests run
+/// on their own tasks and report back through `tx`, so a slow engine
+/// never holds up a redraw or a key press.
+fn refresh_git_info(
+    client: &ApiClient,
+    state: &mut AppState,
+    tx: &mpsc::UnboundedSender<AppEvent>,
+) {
+    let Some(agent) = state.current() else {
+        return;
+    };
+    if agent.git_branch.is_none() || agent.cwd.is_empty() {
+        return;
+    }
+    let repo = agent.cwd.clone();
+    let now = Instant::now();
+    if state.git.worktrees_need_refresh(now) {
+        state.git.touch_worktrees(now);
+        let (client, tx) = (client.clone(), tx.clone());
+        tokio::spawn(async move {
+            let list = client.list_worktrees().await.unwrap_or_else(|e| {
+                tracing::debug!("worktrees: {e:#}");
+                Vec::new()
+            });
+            let _ = tx.send(AppEvent::Worktrees(list));
+        });
+    }
+    if state.git.needs_refresh(&repo, now) {
+        state.git.touch(&repo, now);
+        let (client, tx) = (client.clone(), tx.clone());
+        tokio::spawn(async move {
+            let info = match client.git_branches(&repo).await {
+                Ok(info) => Some(info),
+                Err(e) => {
+                    tracing::debug!("git branches {repo}: {e:#}");
+                    None
+                }
+            };
+            let _ = tx.send(AppEvent::GitBranches { repo, info });
+        });
+    }
+}
+
+/// Best effort: a tmai-core without `/api/bootstrap` just shows no
+/// vendor strip.
+async fn refresh_vendors(client: &ApiClient, state: &mut AppState) {
+    match events::backfill_vendors(client).await {
+        Ok(list) => state.vendors = list,
+        Err(e) => tracing::debug!("vendor backfill: {e:#}"),
+    }
+}
+
 #[allow(clippy::collapsible_match)]
 async fn event_loop(
     terminal: &mut Terminal<CrosstermBackend<Stdout>>,
     client: &ApiClient,
     state: &mut AppState,
     keys: &mut EventStream,
+    ev_tx: &mpsc::UnboundedSender<AppEvent>,
     ev_rx: &mut mpsc::UnboundedReceiver<AppEvent>,
 ) -> Result<()> {
     let mut tick = tokio::time::interval(Duration::from_millis(250));
 
     loop {
+        refresh_git_info(client, state, ev_tx);
         draw(terminal, state)?;
 
         tokio::select! {
@@ -102,6 +366,7 @@ async fn event_loop(
                             return Ok(());
                         }
                     }
+                    Some(Ok(Event::Paste(text))) => state.paste(&text),
                     Some(Err(e)) => {
                         state.status_line = format!("terminal error: {e}");
                     }
@@ -112,24 +377,40 @@ async fn event_loop(
             app_event = ev_rx.recv() => {
                 match app_event {
                     Some(AppEvent::Agents(list)) => {
-                        state.agents = list;
-                        state.clamp();
+                        state.set_agents(list);
+                        complete_finished(client, state).await;
+                        send_initial_prompts(client, state).await;
+                    }
+                    Some(AppEvent::Vendors(list)) => {
+                        state.vendors = list;
                     }
                     Some(AppEvent::Reconnected) => {
                         state.status_line = format!("SSE connected to {}", client.base_url());
                         // Refetch snapshot after reconnect.
                         if let Ok(list) = events::backfill(client).await {
-                            state.agents = list;
-                            state.clamp();
+                            state.set_agents(list);
+                            complete_finished(client, state).await;
+                            send_initial_prompts(client, state).await;
                         }
+                        refresh_vendors(client, state).await;
                     }
                     Some(AppEvent::Disconnected(err)) => {
                         state.status_line = format!("SSE disconnected: {err}");
                     }
+                    Some(AppEvent::GitBranches { repo, info }) => {
+                        state.git.insert(repo, Instant::now(), info);
+                    }
+                    Some(AppEvent::Worktrees(list)) => {
+                        state.git.set_worktrees(Instant::now(), list);
+                    }
                     None => {}
                 }
             }
-            _ = tick.tick() => {}
+            _ = tick.tick() => {
+                check_approvals(state);
+                check_finishing(state);
+                expire_initial_prompts(state);
+            }
         }
     }
 }
@@ -149,52 +430,130 @@ async fn handle_key(
     let mode = std::mem::replace(&mut state.input_mode, InputMode::Normal);
     match mode {
         InputMode::Normal => handle_normal(state, client, key).await,
-        InputMode::SendText(buffer) => handle_send_text(state, client, key, buffer).await,
+        InputMode::SendText(input) => handle_send_text(state, client, key, input).await,
+        InputMode::Broadcast(input) => handle_broadcast(state, client, key, input).await,
+        InputMode::Reject { id, input } => handle_reject(state, client, key, id, input).await,
         InputMode::ConfirmKill(id) => handle_confirm_kill(state, client, key, id).await,
+        InputMode::History(view) => handle_history(state, client, key, view).await,
+        InputMode::Diff(view) => handle_diff(state, client, key, view).await,
+        InputMode::Choices(view) => handle_choices(state, client, key, view).await,
+        InputMode::Settings(view) => handle_settings(state, client, key, view).await,
+        InputMode::Worktrees(view) => handle_worktrees(state, client, key, view).await,
+        InputMode::Presets(view) => handle_presets(state, client, key, view).await,
+        InputMode::Templates(view) => handle_templates(state, key, view),
+        InputMode::Logs(view) => handle_logs(state, key, view),
+        // The mode was already reset to Normal above.
+        InputMode::Help => Ok(false),
     }
 }
 
-#[allow(clippy::collapsible_match)]
 async fn handle_normal(
     state: &mut AppState,
     client: &ApiClient,
     key: crossterm::event::KeyEvent,
 ) -> Result<bool> {
-    match key.code {
-        KeyCode::Char('q') | KeyCode::Esc => return Ok(true),
-        KeyCode::Char('j') | KeyCode::Down => {
-            if !state.agents.is_empty() {
-                state.selected = (state.selected + 1) % state.agents.len();
+    let Some(action) = state.chord.feed(&state.keymap, KeyCombo::from(key)) else {
+        return Ok(false);
+    };
+    run_action(state, client, action).await
+}
+
+async fn run_action(state: &mut AppState, client: &ApiClient, action: Action) -> Result<bool> {
+    match action {
+        Action::Quit => return Ok(true),
+        Action::SelectNext => {
+            let range = state.nav_range();
+            if !range.is_empty() {
+                state.selected = if state.selected + 1 >= range.end {
+                    range.start
+                } else {
+                    state.selected + 1
+                };
             }
         }
-        KeyCode::Char('k') | KeyCode::Up => {
-            if !state.agents.is_empty() {
-                state.selected = if state.selected == 0 {
-                    state.agents.len() - 1
+        Action::SelectPrev => {
+            let range = state.nav_range();
+            if !range.is_empty() {
+                state.selected = if state.selected <= range.start {
+                    range.end - 1
                 } else {
                     state.selected - 1
                 };
             }
         }
-        KeyCode::Char('a') => {
+        Action::Pin => {
+            if let Some(agent) = state.current() {
+                let id = agent.id.clone();
+                state.status_line = match state.pins.toggle(&id) {
+                    Ok(true) => format!("pinned {id}"),
+                    Ok(false) => format!("unpinned {id}"),
+                    Err(e) => format!("pin {id}: {e:#}"),
+                };
+                state.regroup(Some(id));
+            }
+        }
+        Action::SortByDuration => {
+            state.sort_by_duration = !state.sort_by_duration;
+            let id = state.current().map(|a| a.id.clone());
+            state.regroup(id);
+            state.status_line = if state.sort_by_duration {
+                "sorted by time in state".into()
+            } else {
+                "sorted by project order".into()
+            };
+        }
+        Action::NextAttention => {
+            let queue = attention::queue(&state.agents, &state.clock, Instant::now());
+            match attention::next(&queue, state.selected) {
+                Some(i) => {
+                    // Don't land on an agent hidden in a folded project.
+                    if !state.nav_range().contains(&i) {
+                        state.fold_projects = false;
+                    }
+                    state.selected = i;
+                    let pos = queue.iter().position(|&q| q == i).unwrap_or(0) + 1;
+                    let agent = &state.agents[i];
+                    state.status_line = format!(
+                        "needs you {pos}/{}: {} ({})",
+                        queue.len(),
+                        agent.id,
+                        attention_label(agent.attention.as_ref())
+                    );
+                }
+                None => state.status_line = "no agent needs attention".into(),
+            }
+        }
+        Action::FoldProjects => {
+            state.fold_projects = !state.fold_projects;
+            if let (true, Some(g)) = (state.fold_projects, state.current_group()) {
+                state.status_line = format!("showing {} only", state.groups[g].key);
+            }
+        }
+        Action::Approve => {
             if let Some(agent) = state.current() {
                 let id = agent.id.clone();
                 match client.approve(&id).await {
-                    Ok(()) => state.status_line = format!("approved {id}"),
+                    Ok(()) => {
+                        state.approvals.start(&id, Instant::now());
+                        state.status_line = format!("approved {id}");
+                    }
                     Err(e) => state.status_line = format!("approve {id}: {e}"),
                 }
             }
         }
-        KeyCode::Char('y') => {
+        Action::SendYes => {
             if let Some(agent) = state.current() {
                 let id = agent.id.clone();
                 match client.send_key(&id, "y").await {
-                    Ok(()) => state.status_line = format!("sent 'y' to {id}"),
+                    Ok(()) => {
+                        state.approvals.start(&id, Instant::now());
+                        state.status_line = format!("sent 'y' to {id}");
+                    }
                     Err(e) => state.status_line = format!("send_key {id}: {e}"),
                 }
             }
         }
-        KeyCode::Char('n') => {
+        Action::SendNo => {
             if let Some(agent) = state.current() {
                 let id = agent.id.clone();
                 match client.send_key(&id, "n").await {
@@ -203,23 +562,154 @@ async fn handle_normal(
                 }
             }
         }
-        KeyCode::Char('i') => {
-            state.input_mode = InputMode::SendText(String::new());
+        Action::Reject => {
+            if let Some(agent) = state.current() {
+                let id = agent.id.clone();
+                if matches!(agent.attention, Some(AgentAttention::halted)) {
+                    state.input_mode = InputMode::Reject {
+                        id,
+                        input: PromptInput::default(),
+                    };
+                } else {
+                    // Escape on a working agent would interrupt it.
+                    state.status_line = format!("{id} is not waiting for approval");
+                }
+            }
+        }
+        Action::Input => {
+            state.input_mode = InputMode::SendText(PromptInput::default());
+        }
+        Action::Broadcast => match state.broadcast_targets() {
+            Some((group, ids)) if !ids.is_empty() => {
+                state.status_line = format!("broadcast to {} agent(s) in {group}", ids.len());
+                state.input_mode = InputMode::Broadcast(PromptInput::default());
+            }
+            _ => state.status_line = "no agents to broadcast to".into(),
+        },
+        Action::History => {
+            if let Some(agent) = state.current() {
+                let (id, context) = (agent.id.clone(), agent.ctx_usage);
+                match fetch_transcript(state, client, &id).await {
+                    Ok(records) => {
+                        let mut view = HistoryView::new(id, &records);
+                        view.context = context;
+                        state.input_mode = InputMode::History(Box::new(view));
+                    }
+                    Err(e) => state.status_line = format!("history {id}: {e}"),
+                }
+            }
         }
-        KeyCode::Char('K') => {
+        Action::Export => {
+            if let Some(agent) = state.current() {
+                let id = agent.id.clone();
+                match fetch_transcript(state, client, &id).await {
+                    Ok(records) => export_transcript(state, &id, &records),
+                    Err(e) => state.status_line = format!("export {id}: {e}"),
+                }
+            }
+        }
+        Action::Diff => {
+            if let Some(agent) = state.current() {
+                let (id, cwd) = (agent.id.clone(), agent.cwd.clone());
+                match fetch_transcript(state, client, &id).await {
+                    // The file on disk is masked like the transcript.
+                    Ok(records) => match diff::pending_edit(&records, |path| {
+                        diff::read_previous(&cwd, path)
+                            .map(|text| state.redactor.redact(&text).into_owned())
+                    }) {
+                        Some(edit) => {
+                            state.input_mode = InputMode::Diff(Box::new(DiffView::new(id, edit)));
+                        }
+                        None => state.status_line = format!("no pending file edit for {id}"),
+                    },
+                    Err(e) => state.status_line = format!("diff {id}: {e}"),
+                }
+            }
+        }
+        Action::Answer => {
+            if let Some(agent) = state.current() {
+                let id = agent.id.clone();
+                match fetch_transcript(state, client, &id).await {
+                    Ok(records) => match choices::pending_questions(&records) {
+                        Some(questions) => {
+                            state.input_mode =
+                                InputMode::Choices(Box::new(ChoicesView::new(id, questions)));
+                        }
+                        None => state.status_line = format!("no pending question for {id}"),
+                    },
+                    Err(e) => state.status_line = format!("answer {id}: {e}"),
+                }
+            }
+        }
+        Action::Settings => {
+            let mut sections = Vec::new();
+            for section in settings::SECTIONS {
+                match client.get_settings(section).await {
+                    Ok(body) => sections.push((*section, body)),
+                    Err(e) => tracing::warn!("settings {section}: {e}"),
+                }
+            }
+            let view = SettingsView::new(&sections);
+            if view.is_empty() {
+                state.status_line = "no editable settings served by tmai-core".into();
+            } else {
+                state.input_mode = InputMode::Settings(Box::new(view));
+            }
+        }
+        Action::Logs => state.input_mode = InputMode::Logs(Box::default()),
+        Action::Help => state.input_mode = InputMode::Help,
+        Action::Worktrees => match client.list_worktrees().await {
+            Ok(list) => {
+                let branches = worktree_branches(client, &list).await;
+                state.input_mode =
+                    InputMode::Worktrees(Box::new(WorktreesView::new(list, branches)));
+            }
+            Err(e) => state.status_line = format!("worktrees: {e}"),
+        },
+        Action::Spawn => {
+            // New agents start next to the selected one by default.
+            let here = state.current().filter(|a| !a.cwd.is_empty());
+            let branch = here.and_then(|a| a.git_branch.clone());
+            let cwd = here
+                .map(|a| a.cwd.clone())
+                .or_else(|| {
+                    std::env::current_dir()
+                        .ok()
+                        .map(|d| d.display().to_string())
+                })
+                .unwrap_or_default();
+            let view = PresetsView::new(&state.presets, cwd, branch);
+            state.input_mode = InputMode::Presets(Box::new(view));
+        }
+        Action::KillPane => {
             if let Some(agent) = state.current() {
                 state.input_mode = InputMode::ConfirmKill(agent.id.clone());
             }
         }
-        KeyCode::Char('r') => match events::backfill(client).await {
+        Action::Finish => {
+            if let Some(agent) = state.current() {
+                let id = agent.id.clone();
+                if state.finishing.contains(&id) {
+                    state.status_line = format!("{id} is already finishing");
+                } else {
+                    match client.send_prompt(&id, finish::WRAP_UP_PROMPT).await {
+                        Ok(r) => {
+                            state.finishing.start(&id, r.queued(), Instant::now());
+                            state.status_line =
+                                format!("finishing {id}: wrap-up prompt {}", r.action);
+                        }
+                        Err(e) => state.status_line = format!("finish {id}: {e}"),
+                    }
+                }
+            }
+        }
+        Action::Refresh => match events::backfill(client).await {
             Ok(list) => {
-                state.agents = list;
-                state.clamp();
+                state.set_agents(list);
                 state.status_line = "refreshed".into();
             }
             Err(e) => state.status_line = format!("refresh: {e}"),
         },
-        _ => {}
     }
     Ok(false)
 }
@@ -228,34 +718,192 @@ async fn handle_send_text(
     state: &mut AppState,
     client: &ApiClient,
     key: crossterm::event::KeyEvent,
-    mut buffer: String,
+    mut input: PromptInput,
 ) -> Result<bool> {
-    match key.code {
-        KeyCode::Esc => {
-            state.input_mode = InputMode::Normal;
-        }
-        KeyCode::Enter => {
-            state.input_mode = InputMode::Normal;
+    if key.code == KeyCode::Tab {
+        open_templates(state, input);
+        return Ok(false);
+    }
+    match input.key(key, &state.prompt_history) {
+        Edit::Cancel => {}
+        Edit::Submit => {
             if let Some(agent) = state.current() {
                 let id = agent.id.clone();
-                match client.send_text(&id, &buffer).await {
-                    Ok(()) => state.status_line = format!("sent text to {id}"),
+                match client.send_text(&id, input.text()).await {
+                    Ok(()) => {
+                        state.remember(input.text());
+                        state.status_line = format!("sent text to {id}");
+                    }
                     Err(e) => state.status_line = format!("send_text {id}: {e}"),
                 }
             }
         }
-        KeyCode::Backspace => {
-            buffer.pop();
-            state.input_mode = InputMode::SendText(buffer);
+        Edit::Editing => state.input_mode = InputMode::SendText(input),
+    }
+    Ok(false)
+}
+
+/// Swap the input for the template list, keeping it to return to.
+fn open_templates(state: &mut AppState, input: PromptInput) {
+    let files = match templates::default_dir().map(|dir| templates::load_dir(&dir)) {
+        Some(Ok(files)) => files,
+        Some(Err(e)) => {
+            tracing::warn!("prompt templates: {e:#}");
+            state.status_line = format!("prompt templates: {e:#}");
+            Vec::new()
         }
-        KeyCode::Char(c) => {
-            buffer.push(c);
-            state.input_mode = InputMode::SendText(buffer);
+        None => Vec::new(),
+    };
+    let list = templates::merge(&state.prompts, files);
+    if list.is_empty() {
+        state.status_line =
+            "no prompt templates; add [[prompts]] or files in ~/.config/tmai/prompts/".into();
+        state.input_mode = InputMode::SendText(input);
+        return;
+    }
+    let vars = state
+        .current()
+        .map(templates::agent_vars)
+        .unwrap_or_default();
+    state.input_mode = InputMode::Templates(Box::new(TemplatesView::new(list, vars, input)));
+}
+
+fn handle_templates(
+    state: &mut AppState,
+    key: crossterm::event::KeyEvent,
+    mut view: Box<TemplatesView>,
+) -> Result<bool> {
+    let text = if let Some(filling) = view.filling.as_mut() {
+        match key.code {
+            KeyCode::Esc => {
+                view.filling = None;
+                None
+            }
+            KeyCode::Enter => view.submit_value(),
+            KeyCode::Backspace => {
+                filling.buffer.pop();
+                None
+            }
+            KeyCode::Char(c) => {
+                filling.buffer.push(c);
+                None
+            }
+            _ => None,
         }
-        _ => {
-            // keep the buffer — user hit a non-printable we don't handle
-            state.input_mode = InputMode::SendText(buffer);
+    } else {
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('q') | KeyCode::Tab => {
+                state.input_mode = InputMode::SendText(view.input);
+                return Ok(false);
+            }
+            KeyCode::Char('j') | KeyCode::Down => {
+                view.select_next();
+                None
+            }
+            KeyCode::Char('k') | KeyCode::Up => {
+                view.select_prev();
+                None
+            }
+            KeyCode::Char(c @ '1'..='9') => {
+                view.select_number(c as usize - '0' as usize);
+                None
+            }
+            KeyCode::Enter => view.choose(),
+            _ => None,
         }
+    };
+    match text {
+        Some(text) => {
+            let mut input = view.input;
+            input.paste(&text);
+            state.input_mode = InputMode::SendText(input);
+        }
+        None => state.input_mode = InputMode::Templates(view),
+    }
+    Ok(false)
+}
+
+async fn handle_reject(
+    state: &mut AppState,
+    client: &ApiClient,
+    key: crossterm::event::KeyEvent,
+    id: String,
+    mut input: PromptInput,
+) -> Result<bool> {
+    match input.key(key, &state.prompt_history) {
+        Edit::Cancel => {}
+        Edit::Submit => {
+            if let Err(e) = client.send_key(&id, "Escape").await {
+                state.status_line = format!("send_key {id}: {e}");
+                return Ok(false);
+            }
+            let feedback = input.text().trim();
+            if feedback.is_empty() {
+                tracing::info!("rejected {id}");
+                state.status_line = format!("rejected {id}");
+                return Ok(false);
+            }
+            match client.send_prompt(&id, feedback).await {
+                Ok(resp) => {
+                    tracing::info!("rejected {id} with feedback ({}): {feedback}", resp.action);
+                    state.remember(feedback);
+                    state.status_line = format!("rejected {id} and sent feedback");
+                }
+                Err(e) => {
+                    tracing::warn!("rejected {id}, feedback not sent: {e}");
+                    state.status_line = format!("rejected {id}, but sending feedback failed: {e}");
+                }
+            }
+        }
+        Edit::Editing => state.input_mode = InputMode::Reject { id, input },
+    }
+    Ok(false)
+}
+
+async fn handle_broadcast(
+    state: &mut AppState,
+    client: &ApiClient,
+    key: crossterm::event::KeyEvent,
+    mut input: PromptInput,
+) -> Result<bool> {
+    match input.key(key, &state.prompt_history) {
+        Edit::Cancel => {}
+        Edit::Submit if input.text().trim().is_empty() => {
+            state.input_mode = InputMode::Broadcast(input);
+        }
+        Edit::Submit => {
+            let Some((group, ids)) = state.broadcast_targets() else {
+                return Ok(false);
+            };
+            let prompt = input.text();
+            let mut delivered = 0;
+            let mut failed = Vec::new();
+            for id in &ids {
+                // tmai-core queues the prompt for agents that are busy.
+                match client.send_prompt(id, prompt).await {
+                    Ok(r) => {
+                        tracing::info!("broadcast to {id}: {}", r.action);
+                        delivered += 1;
+                    }
+                    Err(e) => {
+                        tracing::warn!("broadcast to {id}: {e}");
+                        failed.push(format!("{id}: {e}"));
+                    }
+                }
+            }
+            state.status_line = match failed.as_slice() {
+                [] => format!("broadcast to {group}: {delivered}/{} delivered", ids.len()),
+                _ => format!(
+                    "broadcast to {group}: {delivered}/{} delivered; failed {}",
+                    ids.len(),
+                    failed.join(", ")
+                ),
+            };
+            if delivered > 0 {
+                state.remember(prompt);
+            }
+        }
+        Edit::Editing => state.input_mode = InputMode::Broadcast(input),
     }
     Ok(false)
 }
@@ -285,26 +933,634 @@ async fn handle_confirm_kill(
     Ok(false)
 }
 
+async fn handle_worktrees(
+    state: &mut AppState,
+    client: &ApiClient,
+    key: crossterm::event::KeyEvent,
+    mut view: Box<WorktreesView>,
+) -> Result<bool> {
+    match view.prompt.take() {
+        Some(WorktreePrompt::ConfirmDelete { force }) => {
+            if let (KeyCode::Char('y'), Some(w)) = (key.code, view.current()) {
+                let (repo, name) = (w.repo_path.clone(), w.name.clone());
+                match client.delete_worktree(&repo, &name, force).await {
+                    Ok(()) => {
+                        state.status_line = format!("deleted worktree {name}");
+                        reload_worktrees(state, client, &mut view).await;
+                    }
+                    Err(e) => state.status_line = format!("delete {name}: {e}"),
+                }
+            }
+        }
+        Some(WorktreePrompt::NewName(mut buffer)) => match key.code {
+            KeyCode::Esc => {}
+            KeyCode::Enter if !buffer.trim().is_empty() => {
+                let name = buffer.trim().to_string();
+                // New worktrees branch off the selected worktree's repo.
+                let cwd = view.current().map(|w| w.repo_path.clone());
+                match cwd {
+                    Some(cwd) => match client.spawn_worktree(&name, &cwd).await {
+                        Ok(()) => {
+                            state.status_line = format!("created worktree {name}");
+                            reload_worktrees(state, client, &mut view).await;
+                        }
+                        Err(e) => state.status_line = format!("create {name}: {e}"),
+                    },
+                    None => state.status_line = "no repository to branch from".into(),
+                }
+            }
+            KeyCode::Backspace => {
+                buffer.pop();
+                view.prompt = Some(WorktreePrompt::NewName(buffer));
+            }
+            KeyCode::Char(c) => {
+                buffer.push(c);
+                view.prompt = Some(WorktreePrompt::NewName(buffer));
+            }
+            _ => view.prompt = Some(WorktreePrompt::NewName(buffer)),
+        },
+        None => match key.code {
+            KeyCode::Esc | KeyCode::Char('q') => return Ok(false),
+            KeyCode::Char('j') | KeyCode::Down => view.select_next(),
+            KeyCode::Char('k') | KeyCode::Up => view.select_prev(),
+            KeyCode::Char('n') => view.prompt = Some(WorktreePrompt::NewName(String::new())),
+            KeyCode::Char('D') => {
+                if let Err(msg) = view.request_delete() {
+                    state.status_line = msg.into();
+                }
+            }
+            KeyCode::Char('r') => reload_worktrees(state, client, &mut view).await,
+            _ => {}
+        },
+    }
+    state.input_mode = InputMode::Worktrees(view);
+    Ok(false)
+}
+
+async fn handle_presets(
+    state: &mut AppState,
+    client: &ApiClient,
+    key: crossterm::event::KeyEvent,
+    mut view: Box<PresetsView>,
+) -> Result<bool> {
+    if let Some(mut task) = view.task.take() {
+        match key.code {
+            KeyCode::Esc => {}
+            KeyCode::Enter if !task.trim().is_empty() => {
+                if spawn_preset(state, client, &view, task.trim()).await {
+                    return Ok(false);
+                }
+            }
+            KeyCode::Backspace => {
+                task.pop();
+                view.task = Some(task);
+            }
+            KeyCode::Char(c) => {
+                task.push(c);
+                view.task = Some(task);
+            }
+            _ => view.task = Some(task),
+        }
+        state.input_mode = InputMode::Presets(view);
+        return Ok(false);
+    }
+    match key.code {
+        KeyCode::Esc | KeyCode::Char('q') => return Ok(false),
+        KeyCode::Char('j') | KeyCode::Down => view.select_next(),
+        KeyCode::Char('k') | KeyCode::Up => view.select_prev(),
+        KeyCode::Char(c @ '1'..='9') => {
+            view.select_number(c as usize - '0' as usize);
+        }
+        KeyCode::Enter => match view.current() {
+            Some(preset) if preset.needs_task() => view.task = Some(String::new()),
+            Some(_) if spawn_preset(state, client, &view, "").await => return Ok(false),
+            _ => {}
+        },
+        _ => {}
+    }
+    state.input_mode = InputMode::Presets(view);
+    Ok(false)
+}
+
+/// Spawn the selected preset and queue its initial prompt. Returns whether
+/// the agent was spawned.
+async fn spawn_preset(
+    state: &mut AppState,
+    client: &ApiClient,
+    view: &PresetsView,
+    task: &str,
+) -> bool {
+    let Some(preset) = view.current() else {
+        return false;
+    };
+    let cwd = view.cwd_for(preset);
+    let resp = match client.spawn(&preset.command, &preset.args, cwd).await {
+        Ok(resp) => resp,
+        Err(e) => {
+            state.status_line = format!("spawn {}: {e}", preset.name);
+            return false;
+        }
+    };
+    state.status_line = format!("spawned {} (pid {}) in {cwd}", preset.name, resp.pid);
+    if let Some(prompt) = view.initial_prompt(preset, task) {
+        state
+            .status_line
+            .push_str(", first prompt waits for the agent");
+        state.initial_prompts.push(
+            resp.session_id.clone(),
+            preset.name.clone(),
+            prompt,
+            Instant::now(),
+        );
+    }
+    if let Ok(list) = events::backfill(client).await {
+        state.set_agents(list);
+        send_initial_prompts(client, state).await;
+    }
+    true
+}
+
+fn handle_logs(
+    state: &mut AppState,
+    key: crossterm::event::KeyEvent,
+    mut view: Box<LogsView>,
+) -> Result<bool> {
+    if view.editing {
+        match key.code {
+            KeyCode::Enter => view.editing = false,
+            KeyCode::Esc => {
+                view.editing = false;
+                view.query.clear();
+            }
+            KeyCode::Backspace => {
+                view.query.pop();
+            }
+            KeyCode::Char(c) => view.query.push(c),
+            _ => {}
+        }
+        view.scroll = 0;
+        state.input_mode = InputMode::Logs(view);
+        return Ok(false);
+    }
+    match key.code {
+        KeyCode::Esc | KeyCode::Char('q') => return Ok(false),
+        KeyCode::Char('k') | KeyCode::Up => view.scroll += 1,
+        KeyCode::Char('j') | KeyCode::Down => view.scroll = view.scroll.saturating_sub(1),
+        KeyCode::PageUp => view.scroll += 20,
+        KeyCode::PageDown => view.scroll = view.scroll.saturating_sub(20),
+        KeyCode::Char('g') => view.scroll = usize::MAX,
+        KeyCode::Char('G') => view.scroll = 0,
+        KeyCode::Char('f') => view.cycle_level(),
+        KeyCode::Char('/') => view.editing = true,
+        KeyCode::Char('v') => {
+            state.status_line = match state.log_level.cycle() {
+                Ok(directives) => format!("log filter: {directives}"),
+                Err(e) => format!("log filter: {e:#}"),
+            };
+        }
+        _ => {}
+    }
+    // Don't let the offset run past the oldest line.
+    let lines = state.logs.snapshot();
+    let max = view.visible(&lines).len().saturating_sub(1);
+    view.scroll = view.scroll.min(max);
+    state.input_mode = InputMode::Logs(view);
+    Ok(false)
+}
+
+async fn reload_worktrees(state: &mut AppState, client: &ApiClient, view: &mut WorktreesView) {
+    match client.list_worktrees().await {
+        Ok(list) => {
+            let branches = worktree_branches(client, &list).await;
+            view.reload(list, branches);
+        }
+        Err(e) => state.status_line = format!("worktrees: {e}"),
+    }
+}
+
+/// `/git/branches` for each repository in `list`, for the stale check.
+/// Best effort: a repo whose fetch fails is left out, and its idle
+/// worktrees are never flagged stale.
+async fn worktree_branches(
+    client: &ApiClient,
+    list: &[WorktreeSnapshot],
+) -> HashMap<String, BranchListResponse> {
+    let repos: BTreeSet<&str> = list.iter().map(|w| w.repo_path.as_str()).collect();
+    let mut branches = HashMap::new();
+    for repo in repos {
+        match client.git_branches(repo).await {
+            Ok(info) => {
+                branches.insert(repo.to_string(), info);
+            }
+            Err(e) => tracing::debug!("git branches {repo}: {e:#}"),
+        }
+    }
+    branches
+}
+
+/// Warn about approvals that didn't take.
+fn check_approvals(state: &mut AppState) {
+    let stuck = state.approvals.stuck(&state.agents, Instant::now());
+    if stuck.is_empty() {
+        return;
+    }
+    for id in &stuck {
+        tracing::warn!(
+            "{id} still halted {}s after approval",
+            approvals::WINDOW.as_secs()
+        );
+    }
+    state.status_line = format!(
+        "approval may not have reached {}: still halted, check the pane",
+        stuck.join(", ")
+    );
+}
+
+/// Warn about wrap-up prompts the agent was never seen working on. The
+/// turn may have run between snapshots, so the pane is left for the user
+/// to check rather than killed.
+fn check_finishing(state: &mut AppState) {
+    let stuck = state.finishing.stuck(Instant::now());
+    if stuck.is_empty() {
+        return;
+    }
+    for id in &stuck {
+        tracing::warn!(
+            "{id} not seen working {}s after the wrap-up prompt",
+            finish::SENT_WINDOW.as_secs()
+        );
+    }
+    state.status_line = format!(
+        "finish {}: wrap-up turn not seen, not killed; check the pane",
+        stuck.join(", ")
+    );
+}
+
+/// Send each preset's initial prompt once its spawned agent is listed.
+async fn send_initial_prompts(client: &ApiClient, state: &mut AppState) {
+    for (id, pending) in state.initial_prompts.take_ready(&state.agents) {
+        state.status_line = match client.send_prompt(&id, &pending.prompt).await {
+            Ok(r) => format!("{} ({id}): first prompt {}", pending.preset, r.action),
+            Err(e) => format!("{} ({id}): first prompt failed: {e}", pending.preset),
+        };
+    }
+}
+
+fn expire_initial_prompts(state: &mut AppState) {
+    for pending in state.initial_prompts.take_expired(Instant::now()) {
+        state.status_line = format!(
+            "{}: agent never appeared, first prompt not sent",
+            pending.preset
+        );
+        tracing::warn!(
+            "initial prompt for spawn {} dropped: no agent after {}s",
+            pending.session_id,
+            presets::PROMPT_WAIT.as_secs()
+        );
+    }
+}
+
+/// Save the transcript of every agent that has finished its wrap-up turn,
+/// then kill its pane. An agent whose transcript can't be saved is left
+/// running so nothing is lost.
+async fn complete_finished(client: &ApiClient, state: &mut AppState) {
+    for id in state.finishing.observe(&state.agents, Instant::now()) {
+        let records = match fetch_transcript(state, client, &id).await {
+            Ok(records) => records,
+            Err(e) => {
+                state.status_line = format!("finish {id}: transcript: {e}; not killed");
+                continue;
+            }
+        };
+        let path = match export::write_markdown(&export::default_dir(), &id, &records) {
+            Ok(path) => path,
+            Err(e) => {
+                state.status_line = format!("finish {id}: {e:#}; not killed");
+                continue;
+            }
+        };
+        let summary = finish::summary_line(&records).unwrap_or("no summary");
+        state.status_line = match client.kill(&id).await {
+            Ok(()) => format!("finished {id}: {summary} (saved {})", path.display()),
+            Err(e) => format!("finish {id}: saved {}, kill failed: {e}", path.display()),
+        };
+        tracing::info!("finished {id}, transcript at {}", path.display());
+    }
+}
+
+/// Transcript with secrets masked — every view that shows transcript
+/// content goes through here.
+async fn fetch_transcript(
+    state: &AppState,
+    client: &ApiClient,
+    id: &str,
+) -> Result<Vec<TranscriptRecord>> {
+    let mut records = client.transcript(id).await?;
+    state.redactor.redact_records(&mut records);
+    Ok(records)
+}
+
+fn export_transcript(state: &mut AppState, id: &str, records: &[TranscriptRecord]) {
+    state.status_line = match export::write_markdown(&export::default_dir(), id, records) {
+        Ok(path) => format!("exported {id} to {}", path.display()),
+        Err(e) => format!("export {id}: {e:#}"),
+    };
+}
+
+async fn handle_history(
+    state: &mut AppState,
+    client: &ApiClient,
+    key: crossterm::event::KeyEvent,
+    mut view: Box<HistoryView>,
+) -> Result<bool> {
+    if let Some(mut buffer) = view.search_input.take() {
+        match key.code {
+            KeyCode::Esc => {}
+            KeyCode::Enter => {
+                view.search(&buffer);
+                if view.match_position().is_none() && !buffer.is_empty() {
+                    state.status_line = format!("no match for '{buffer}'");
+                }
+            }
+            KeyCode::Backspace => {
+                buffer.pop();
+                view.search_input = Some(buffer);
+            }
+            KeyCode::Char(c) => {
+                buffer.push(c);
+                view.search_input = Some(buffer);
+            }
+            _ => view.search_input = Some(buffer),
+        }
+        state.input_mode = InputMode::History(view);
+        return Ok(false);
+    }
+
+    match key.code {
+        KeyCode::Esc | KeyCode::Char('q') => return Ok(false),
+        KeyCode::Char('k') | KeyCode::Up => view.scroll_up(1),
+        KeyCode::Char('j') | KeyCode::Down => view.scroll_down(1),
+        KeyCode::PageUp => view.scroll_up(view.page()),
+        KeyCode::PageDown | KeyCode::Char(' ') => view.scroll_down(view.page()),
+        KeyCode::Char('g') | KeyCode::Home => view.to_top(),
+        KeyCode::Char('G') | KeyCode::End => view.to_tail(),
+        KeyCode::Char('/') => view.search_input = Some(String::new()),
+        KeyCode::Char('n') => view.prev_match(),
+        KeyCode::Char('N') => view.next_match(),
+        KeyCode::Char('s') => view.show_stats = !view.show_stats,
+        KeyCode::Char('e') => match fetch_transcript(state, client, &view.agent_id).await {
+            Ok(records) => export_transcript(state, &view.agent_id, &records),
+            Err(e) => state.status_line = format!("export {}: {e}", view.agent_id),
+        },
+        KeyCode::Char('r') => match fetch_transcript(state, client, &view.agent_id).await {
+            Ok(records) => {
+                view.reload(&records);
+                view.context = state
+                    .agents
+                    .iter()
+                    .find(|a| a.id == view.agent_id)
+                    .and_then(|a| a.ctx_usage);
+                state.status_line = format!("reloaded history for {}", view.agent_id);
+            }
+            Err(e) => state.status_line = format!("history {}: {e}", view.agent_id),
+        },
+        _ => {}
+    }
+    state.input_mode = InputMode::History(view);
+    Ok(false)
+}
+
+async fn handle_diff(
+    state: &mut AppState,
+    client: &ApiClient,
+    key: crossterm::event::KeyEvent,
+    mut view: Box<DiffView>,
+) -> Result<bool> {
+    match key.code {
+        KeyCode::Esc | KeyCode::Char('q') => return Ok(false),
+        KeyCode::Char('j') | KeyCode::Down => view.scroll_down(1),
+        KeyCode::Char('k') | KeyCode::Up => view.scroll_up(1),
+        KeyCode::PageDown | KeyCode::Char(' ') => view.scroll_down(20),
+        KeyCode::PageUp => view.scroll_up(20),
+        KeyCode::Char('a') => {
+            let id = view.agent_id.clone();
+            match client.approve(&id).await {
+                Ok(()) => {
+                    state.approvals.start(&id, Instant::now());
+                    state.status_line = format!("approved {id}");
+                }
+                Err(e) => state.status_line = format!("approve {id}: {e}"),
+            }
+            return Ok(false);
+        }
+        KeyCode::Char('n') => {
+            let id = view.agent_id.clone();
+            match client.send_key(&id, "n").await {
+                Ok(()) => state.status_line = format!("sent 'n' to {id}"),
+                Err(e) => state.status_line = format!("send_key {id}: {e}"),
+            }
+            return Ok(false);
+        }
+        _ => {}
+    }
+    state.input_mode = InputMode::Diff(view);
+    Ok(false)
+}
+
+async fn handle_choices(
+    state: &mut AppState,
+    client: &ApiClient,
+    key: crossterm::event::KeyEvent,
+    mut view: Box<ChoicesView>,
+) -> Result<bool> {
+    match key.code {
+        KeyCode::Esc | KeyCode::Char('q') => return Ok(false),
+        KeyCode::Char('j') | KeyCode::Down => view.select_next(),
+        KeyCode::Char('k') | KeyCode::Up => view.select_prev(),
+        KeyCode::Char(' ') => view.toggle(),
+        KeyCode::Char(c @ '1'..='9') => view.pick_number(c as usize - '0' as usize),
+        KeyCode::Enter => {
+            let id = view.agent_id.clone();
+            let Some(keys) = view.keys() else {
+                state.status_line = if view.question.multi_select {
+                    "check at least one option (Space)".into()
+                } else {
+                    "option has no number key; answer it in the pane".into()
+                };
+                state.input_mode = InputMode::Choices(view);
+                return Ok(false);
+            };
+            for k in &keys {
+                if let Err(e) = client.send_key(&id, k).await {
+                    state.status_line = format!("send_key {id}: {e}");
+                    return Ok(false);
+                }
+            }
+            state.approvals.start(&id, Instant::now());
+            state.status_line = match view.remaining {
+                0 => format!("answered {id}: {}", view.answer()),
+                n => format!(
+                    "answered {id}: {}; {n} more question(s) in the pane",
+                    view.answer()
+                ),
+            };
+            return Ok(false);
+        }
+        _ => {}
+    }
+    state.input_mode = InputMode::Choices(view);
+    Ok(false)
+}
+
+async fn handle_settings(
+    state: &mut AppState,
+    client: &ApiClient,
+    key: crossterm::event::KeyEvent,
+    mut view: Box<SettingsView>,
+) -> Result<bool> {
+    if let Some(mut buffer) = view.editing.take() {
+        match key.code {
+            KeyCode::Esc => {}
+            KeyCode::Enter => {
+                view.editing = Some(buffer);
+                if let Err(e) = view.commit_edit() {
+                    state.status_line = e;
+                }
+            }
+            KeyCode::Backspace => {
+                buffer.pop();
+                view.editing = Some(buffer);
+            }
+            KeyCode::Char(c) => {
+                buffer.push(c);
+                view.editing = Some(buffer);
+            }
+            _ => view.editing = Some(buffer),
+        }
+        state.input_mode = InputMode::Settings(view);
+        return Ok(false);
+    }
+
+    match key.code {
+        KeyCode::Esc | KeyCode::Char('q') => {
+            if view.dirty_count() > 0 {
+                state.status_line = format!("discarded {} unsaved setting(s)", view.dirty_count());
+            }
+            return Ok(false);
+        }
+        KeyCode::Char('j') | KeyCode::Down => view.select_next(),
+        KeyCode::Char('k') | KeyCode::Up => view.select_prev(),
+        KeyCode::Enter | KeyCode::Char(' ') => view.activate(),
+        KeyCode::Char('u') => view.discard(),
+        KeyCode::Char('s') => {
+            let patches = view.patches();
+            if patches.is_empty() {
+                state.status_line = "nothing to save".into();
+            }
+            let mut saved = 0;
+            for (section, patch) in patches {
+                match client.update_settings(section, &patch).await {
+                    Ok(()) => {
+                        saved += patch.as_object().map_or(0, |m| m.len());
+                        view.mark_saved(section);
+                    }
+                    Err(e) => {
+                        state.status_line = format!("save {section}: {e}");
+                        state.input_mode = InputMode::Settings(view);
+                        return Ok(false);
+                    }
+                }
+            }
+            if saved > 0 {
+                state.status_line = format!("saved {saved} setting(s)");
+            }
+        }
+        _ => {}
+    }
+    state.input_mode = InputMode::Settings(view);
+    Ok(false)
+}
+
 fn draw(terminal: &mut Terminal<CrosstermBackend<Stdout>>, state: &AppState) -> Result<()> {
     let kill_prompt = if let InputMode::ConfirmKill(id) = &state.input_mode {
         format!("kill agent {id}? (y/n)")
     } else {
         String::new()
     };
+    let broadcast_title = match state.broadcast_targets() {
+        Some((group, ids)) => format!(
+            " broadcast to {} agent(s) in {group} (Enter to send, Esc to cancel) ",
+            ids.len()
+        ),
+        None => String::new(),
+    };
+    let reject_title = match &state.input_mode {
+        InputMode::Reject { id, .. } => {
+            format!(" reject {id}: what should it do instead? (Enter to reject, Esc to cancel) ")
+        }
+        _ => String::new(),
+    };
     terminal.draw(|frame| {
         let area = frame.area();
+        // Full-screen overlays replace the list entirely.
         let input_mode_view = match &state.input_mode {
+            InputMode::History(view) => {
+                return history::render(frame, area, view, &state.status_line)
+            }
+            InputMode::Diff(view) => return diff::render(frame, area, view, &state.status_line),
+            InputMode::Choices(view) => {
+                return choices::render(frame, area, view, &state.status_line)
+            }
+            InputMode::Settings(view) => {
+                return settings::render(frame, area, view, &state.status_line)
+            }
+            InputMode::Worktrees(view) => {
+                return worktrees::render(frame, area, view, &state.status_line)
+            }
+            InputMode::Presets(view) => {
+                return presets::render(frame, area, view, &state.status_line)
+            }
+            InputMode::Templates(view) => {
+                return templates::render(frame, area, view, &state.status_line)
+            }
+            InputMode::Logs(view) => {
+                let lines = state.logs.snapshot();
+                let capture = state.log_level.directives();
+                return logs::render(frame, area, view, &lines, &capture, &state.status_line);
+            }
+            InputMode::Help => return help::render(frame, area, &state.keymap, &state.status_line),
             InputMode::Normal => InputModeView::Normal,
-            InputMode::SendText(buffer) => InputModeView::Text { buffer },
+            InputMode::SendText(input) => InputModeView::Text {
+                title: " send text (Enter to send, Alt+Enter new line, Up/Down history, Tab templates, Esc to cancel) ",
+                buffer: input.text(),
+            },
+            InputMode::Broadcast(input) => InputModeView::Text {
+                title: &broadcast_title,
+                buffer: input.text(),
+            },
+            InputMode::Reject { input, .. } => InputModeView::Text {
+                title: &reject_title,
+                buffer: input.text(),
+            },
             InputMode::ConfirmKill(_) => InputModeView::Confirm {
                 prompt: &kill_prompt,
             },
         };
+        let detail = state
+            .current()
+            .and_then(|a| git_info::summary(a, state.git.get(&a.cwd), state.git.worktree_of(a)));
         let view = SessionListView {
             agents: &state.agents,
+            groups: &state.groups,
+            unfolded: state.current_group().filter(|_| state.fold_projects),
+            pins: &state.pins,
+            clock: &state.clock,
+            vendors: &state.vendors,
             selected: state.selected,
+            detail: detail.as_deref(),
             input_mode: input_mode_view,
             status_line: &state.status_line,
+            keymap: &state.keymap,
         };
         render(frame, area, view);
     })?;
@@ -314,7 +1570,12 @@ fn draw(terminal: &mut Terminal<CrosstermBackend<Stdout>>, state: &AppState) ->
 fn setup_terminal() -> Result<Terminal<CrosstermBackend<Stdout>>> {
     enable_raw_mode()?;
     let mut stdout = std::io::stdout();
-    execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
+    execute!(
+        stdout,
+        EnterAlternateScreen,
+        EnableMouseCapture,
+        EnableBracketedPaste
+    )?;
     let backend = CrosstermBackend::new(stdout);
     let terminal = Terminal::new(backend)?;
     Ok(terminal)
@@ -325,7 +1586,8 @@ fn teardown_terminal(terminal: &mut Terminal<CrosstermBackend<Stdout>>) -> Resul
     execute!(
         terminal.backend_mut(),
         LeaveAlternateScreen,
-        DisableMouseCapture
+        DisableMouseCapture,
+        DisableBracketedPaste
     )?;
     terminal.show_cursor()?;
     Ok(())
@@ -0,0 +1,74 @@
+//! Check that an approval actually took.
+//!
+//! `POST /approve` succeeding only means the keys were sent; a focus quirk
+//! in the pane can swallow them and leave the agent halted while the
+//! status line says "approved". Each approval sent from this client is
+//! tracked for [`WINDOW`]; an agent still halted after that is reported.
+//! Nothing is re-sent automatically: a second approval landing on the
+//! agent's *next* prompt would be worse than a stuck one, and the right
+//! alternate keys depend on tmai-core's detector.
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use crate::types::{AgentAttention, AgentSnapshot};
+
+/// How long an agent may stay halted after an approval.
+pub const WINDOW: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Default)]
+pub struct ApprovalCheck {
+    /// Agent id → when the approval was sent.
+    pending: HashMap<String, Instant>,
+}
+
+impl ApprovalCheck {
+    pub fn start(&mut self, id: &str, now: Instant) {
+        self.pending.insert(id.to_string(), now);
+    }
+
+    /// Agents still halted [`WINDOW`] after their approval, which are no
+    /// longer tracked. Agents that moved on or vanished are dropped.
+    pub fn stuck(&mut self, agents: &[AgentSnapshot], now: Instant) -> Vec<String> {
+        let mut stuck = Vec::new();
+        self.pending.retain(|id, sent| {
+            let halted = agents
+                .iter()
+                .find(|a| a.id == *id)
+                .is_some_and(|a| matches!(a.attention, Some(AgentAttention::halted)));
+            if !halted {
+                return false;
+            }
+            if now.duration_since(*sent) >= WINDOW {
+                stuck.push(id.clone());
+                return false;
+            }
+            true
+        });
+        stuck.sort();
+        stuck
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::test_support::agent;
+
+    #[test]
+    fn reports_agents_still_halted_after_window() {
+        let t0 = Instant::now();
+        let mut check = ApprovalCheck::default();
+        check.start("a", t0);
+        check.start("b", t0);
+        let agents = [agent("a", Some("halted")), agent("b", Some("halted"))];
+        assert!(check.stuck(&agents, t0 + Duration::from_secs(1)).is_empty());
+
+        // b moved on; a didn't.
+        let agents = [agent("a", Some("halted")), agent("b", None)];
+        assert!(check.stuck(&agents, t0 + Duration::from_secs(2)).is_empty());
+        assert_eq!(check.stuck(&agents, t0 + WINDOW), ["a"]);
+        // Reported once.
+        assert!(check.stuck(&agents, t0 + WINDOW * 2).is_empty());
+    }
+}
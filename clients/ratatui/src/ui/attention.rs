@@ -0,0 +1,75 @@
+//! Attention queue: the agents waiting on the user, most urgent first.
+//!
+//! Halted agents (blocked on an approval or error) come before started
+//! ones (waiting for a first prompt); within a priority the agent that has
+//! waited longest comes first. The `next_attention` action walks this
+//! queue so the two agents that need you can be reached without scrolling
+//! past the thirteen that don't.
+
+use std::time::Instant;
+
+use crate::types::{AgentAttention, AgentSnapshot};
+use crate::ui::durations::StateClock;
+
+fn priority(agent: &AgentSnapshot) -> Option<u8> {
+    match agent.attention {
+        Some(AgentAttention::halted) => Some(0),
+        Some(AgentAttention::started) => Some(1),
+        Some(AgentAttention::completed) | None => None,
+    }
+}
+
+/// Indices into `agents`, in queue order.
+pub fn queue(agents: &[AgentSnapshot], clock: &StateClock, now: Instant) -> Vec<usize> {
+    let mut q: Vec<(u8, std::cmp::Reverse<Option<std::time::Duration>>, usize)> = agents
+        .iter()
+        .enumerate()
+        .filter_map(|(i, a)| {
+            let waited = clock.elapsed(&a.id, now).map(|(d, _)| d);
+            priority(a).map(|p| (p, std::cmp::Reverse(waited), i))
+        })
+        .collect();
+    q.sort();
+    q.into_iter().map(|(_, _, i)| i).collect()
+}
+
+/// The queue entry after `selected`, wrapping; the head of the queue when
+/// `selected` isn't in it.
+pub fn next(queue: &[usize], selected: usize) -> Option<usize> {
+    match queue.iter().position(|&i| i == selected) {
+        Some(pos) => queue.get((pos + 1) % queue.len()).copied(),
+        None => queue.first().copied(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::test_support::agent;
+    use std::time::Duration;
+
+    #[test]
+    fn halted_first_then_longest_waiting() {
+        let t0 = Instant::now();
+        let mut clock = StateClock::default();
+        clock.observe(&[], t0);
+        let early = [agent("s1", Some("started")), agent("h1", Some("halted"))];
+        clock.observe(&early, t0);
+        let agents = [
+            agent("s1", Some("started")),
+            agent("h1", Some("halted")),
+            agent("r", None),
+            agent("h2", Some("halted")),
+            agent("d", Some("completed")),
+        ];
+        let t1 = t0 + Duration::from_secs(60);
+        clock.observe(&agents, t1);
+        let q = queue(&agents, &clock, t1);
+        assert_eq!(q, [1, 3, 0]);
+
+        assert_eq!(next(&q, 1), Some(3));
+        assert_eq!(next(&q, 0), Some(1));
+        assert_eq!(next(&q, 2), Some(1));
+        assert_eq!(next(&[], 2), None);
+    }
+}
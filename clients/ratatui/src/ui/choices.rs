@@ -0,0 +1,321 @@
+//! Picker for an agent's pending multiple-choice question.
+//!
+//! Claude Code asks the user to choose through its `AskUserQuestion`
+//! tool. While it waits, the call is the newest `tool_use` in the
+//! transcript with no `tool_result`, and its input lists the questions and
+//! options. This view shows the first question as a list; confirming sends
+//! the option's number key to the pane (the CLI's own shortcut), or for a
+//! multi-select question each checked number followed by `Enter`. Further
+//! questions in the same call are left to be answered in the pane.
+
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Wrap},
+    Frame,
+};
+use serde_json::Value;
+
+use crate::types::TranscriptRecord;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Choice {
+    pub label: String,
+    pub description: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Question {
+    pub header: String,
+    pub question: String,
+    pub options: Vec<Choice>,
+    pub multi_select: bool,
+}
+
+/// Questions of the newest `AskUserQuestion` call that has no result yet.
+pub fn pending_questions(records: &[TranscriptRecord]) -> Option<Vec<Question>> {
+    for record in records.iter().rev() {
+        match record {
+            TranscriptRecord::ToolResult { .. } => return None,
+            TranscriptRecord::ToolUse {
+                tool_name,
+                input_full,
+                ..
+            } => {
+                if tool_name != "AskUserQuestion" {
+                    return None;
+                }
+                return questions_from_input(input_full.as_ref()?);
+            }
+            _ => continue,
+        }
+    }
+    None
+}
+
+fn questions_from_input(input: &Value) -> Option<Vec<Question>> {
+    let text = |v: &Value, key: &str| {
+        v.get(key)
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_string()
+    };
+    let questions: Vec<Question> = input
+        .get("questions")?
+        .as_array()?
+        .iter()
+        .map(|q| Question {
+            header: text(q, "header"),
+            question: text(q, "question"),
+            options: q
+                .get("options")
+                .and_then(Value::as_array)
+                .map(|opts| {
+                    opts.iter()
+                        .map(|o| Choice {
+                            label: text(o, "label"),
+                            description: text(o, "description"),
+                        })
+                        .collect()
+                })
+                .unwrap_or_default(),
+            multi_select: q
+                .get("multiSelect")
+                .and_then(Value::as_bool)
+                .unwrap_or(false),
+        })
+        .filter(|q| !q.options.is_empty())
+        .collect();
+    (!questions.is_empty()).then_some(questions)
+}
+
+#[derive(Debug, Clone)]
+pub struct ChoicesView {
+    pub agent_id: String,
+    pub question: Question,
+    /// Questions after this one in the same call.
+    pub remaining: usize,
+    pub cursor: usize,
+    /// Checked options of a multi-select question.
+    pub checked: Vec<bool>,
+}
+
+impl ChoicesView {
+    pub fn new(agent_id: String, mut questions: Vec<Question>) -> Self {
+        let question = questions.remove(0);
+        let checked = vec![false; question.options.len()];
+        Self {
+            agent_id,
+            question,
+            remaining: questions.len(),
+            cursor: 0,
+            checked,
+        }
+    }
+
+    pub fn select_next(&mut self) {
+        self.cursor = (self.cursor + 1) % self.question.options.len();
+    }
+
+    pub fn select_prev(&mut self) {
+        let n = self.question.options.len();
+        self.cursor = (self.cursor + n - 1) % n;
+    }
+
+    /// Move to option `n` (1-based); on a multi-select question also
+    /// toggle it.
+    pub fn pick_number(&mut self, n: usize) {
+        if (1..=self.question.options.len()).contains(&n) {
+            self.cursor = n - 1;
+            if self.question.multi_select {
+                self.toggle();
+            }
+        }
+    }
+
+    pub fn toggle(&mut self) {
+        if self.question.multi_select {
+            self.checked[self.cursor] = !self.checked[self.cursor];
+        }
+    }
+
+    /// Keys to send to the pane to answer, in tmux key-name form. `None`
+    /// when the answer can't be typed as number keys (a multi-select with
+    /// nothing checked, or an option past 9).
+    pub fn keys(&self) -> Option<Vec<String>> {
+        let digit = |i: usize| (i < 9).then(|| (i + 1).to_string());
+        if !self.question.multi_select {
+            return digit(self.cursor).map(|d| vec![d]);
+        }
+        let mut keys = self
+            .checked
+            .iter()
+            .enumerate()
+            .filter(|(_, c)| **c)
+            .map(|(i, _)| digit(i))
+            .collect::<Option<Vec<_>>>()?;
+        if keys.is_empty() {
+            return None;
+        }
+        keys.push("Enter".into());
+        Some(keys)
+    }
+
+    /// Labels of what [`Self::keys`] would answer, for the status line.
+    pub fn answer(&self) -> String {
+        if !self.question.multi_select {
+            return self.question.options[self.cursor].label.clone();
+        }
+        self.question
+            .options
+            .iter()
+            .zip(&self.checked)
+            .filter(|(_, c)| **c)
+            .map(|(o, _)| o.label.as_str())
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+pub fn render(frame: &mut Frame, area: Rect, view: &ChoicesView, status_line: &str) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(4), // question
+            Constraint::Min(1),    // options
+            Constraint::Length(3), // hint box
+            Constraint::Length(1), // status
+        ])
+        .split(area);
+
+    let q = &view.question;
+    let more = match view.remaining {
+        0 => String::new(),
+        n => format!(", {n} more in the pane"),
+    };
+    let title = format!(" {} — {}{more} ", q.header, view.agent_id);
+    frame.render_widget(
+        Paragraph::new(q.question.clone())
+            .wrap(Wrap { trim: true })
+            .block(Block::default().borders(Borders::ALL).title(title)),
+        chunks[0],
+    );
+
+    let items: Vec<ListItem> = q
+        .options
+        .iter()
+        .enumerate()
+        .map(|(i, o)| {
+            let mark = match (q.multi_select, view.checked[i]) {
+                (false, _) => String::new(),
+                (true, true) => "[x] ".into(),
+                (true, false) => "[ ] ".into(),
+            };
+            let mut spans = vec![
+                Span::styled(format!("{}. ", i + 1), Style::default().fg(Color::DarkGray)),
+                Span::raw(format!("{mark}{}", o.label)),
+            ];
+            if !o.description.is_empty() {
+                spans.push(Span::styled(
+                    format!("  {}", o.description),
+                    Style::default().fg(Color::DarkGray),
+                ));
+            }
+            ListItem::new(Line::from(spans))
+        })
+        .collect();
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(if q.multi_select {
+                    " choose any "
+                } else {
+                    " choose one "
+                }),
+        )
+        .highlight_style(
+            Style::default()
+                .bg(Color::DarkGray)
+                .add_modifier(Modifier::BOLD),
+        )
+        .highlight_symbol("▶ ");
+    let mut state = ListState::default();
+    state.select(Some(view.cursor));
+    frame.render_stateful_widget(list, chunks[1], &mut state);
+
+    let hint = if q.multi_select {
+        "j/k move  Space or 1-9 toggle  Enter send  Esc close"
+    } else {
+        "j/k move  1-9 pick  Enter send  Esc close"
+    };
+    frame.render_widget(
+        Paragraph::new(hint).block(Block::default().borders(Borders::ALL).title(" keys ")),
+        chunks[2],
+    );
+    frame.render_widget(
+        Paragraph::new(status_line.to_string()).style(Style::default().fg(Color::DarkGray)),
+        chunks[3],
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn ask(multi: bool) -> TranscriptRecord {
+        TranscriptRecord::ToolUse {
+            tool_name: "AskUserQuestion".into(),
+            input_summary: String::new(),
+            input_full: Some(json!({"questions": [
+                {"header": "Storage", "question": "Which database?", "multiSelect": multi,
+                 "options": [
+                    {"label": "Postgres", "description": "relational"},
+                    {"label": "SQLite"},
+                    {"label": "Redis"}
+                 ]},
+                {"header": "Auth", "question": "Which auth?", "options": [{"label": "OAuth"}]}
+            ]})),
+            timestamp: None,
+        }
+    }
+
+    #[test]
+    fn finds_only_unanswered_question() {
+        let qs = pending_questions(&[ask(false)]).unwrap();
+        assert_eq!(qs.len(), 2);
+        assert_eq!(qs[0].options[0].description, "relational");
+        let answered = TranscriptRecord::ToolResult {
+            output_summary: "Postgres".into(),
+            is_error: false,
+            timestamp: None,
+        };
+        assert!(pending_questions(&[ask(false), answered]).is_none());
+    }
+
+    #[test]
+    fn single_select_sends_number_key() {
+        let mut v = ChoicesView::new("a".into(), pending_questions(&[ask(false)]).unwrap());
+        assert_eq!(v.remaining, 1);
+        v.select_prev();
+        assert_eq!(v.keys(), Some(vec!["3".to_string()]));
+        assert_eq!(v.answer(), "Redis");
+        v.toggle(); // no-op on single select
+        assert!(v.checked.iter().all(|c| !c));
+    }
+
+    #[test]
+    fn multi_select_sends_checked_numbers_then_enter() {
+        let mut v = ChoicesView::new("a".into(), pending_questions(&[ask(true)]).unwrap());
+        assert_eq!(v.keys(), None);
+        v.pick_number(1);
+        v.pick_number(3);
+        assert_eq!(
+            v.keys(),
+            Some(vec!["1".to_string(), "3".to_string(), "Enter".to_string()])
+        );
+        assert_eq!(v.answer(), "Postgres, Redis");
+    }
+}
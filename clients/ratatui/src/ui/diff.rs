@@ -0,0 +1,397 @@
+//! Diff viewer for a pending file-edit approval.
+//!
+//! When an agent halts on an `Edit` / `MultiEdit` / `Write` permission
+//! prompt, the proposed change is already in its transcript as the last
+//! `tool_use` record without a matching `tool_result`. This view pulls the
+//! tool input out of that record and renders it as a coloured unified diff
+//! so the user can read the change before approving it, instead of
+//! squinting at the raw pane capture.
+//!
+//! An `Edit` carries only the replaced snippet, not the file, so its hunk
+//! line numbers count from the start of the snippet and are labelled as
+//! such. A `Write` carries the whole new file; it is diffed against the
+//! file as it is now, read from the agent's directory (tmai-core runs on
+//! this machine), so overwritten lines show as removals. When the file
+//! can't be read — a new file, or an engine elsewhere — the new contents
+//! are listed under a note saying the previous file isn't shown.
+
+use std::path::Path;
+
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph},
+    Frame,
+};
+use serde_json::Value;
+use similar::{ChangeTag, TextDiff};
+
+use crate::types::TranscriptRecord;
+
+/// Lines of unchanged context kept around each change.
+const CONTEXT: usize = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffLineKind {
+    Header,
+    Hunk,
+    Context,
+    Added,
+    Removed,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiffLine {
+    pub kind: DiffLineKind,
+    pub text: String,
+}
+
+/// A file edit the agent is waiting for permission to apply.
+#[derive(Debug, Clone)]
+pub struct PendingEdit {
+    pub tool_name: String,
+    pub file_path: String,
+    pub lines: Vec<DiffLine>,
+}
+
+impl PendingEdit {
+    pub fn added(&self) -> usize {
+        self.count(DiffLineKind::Added)
+    }
+
+    pub fn removed(&self) -> usize {
+        self.count(DiffLineKind::Removed)
+    }
+
+    fn count(&self, kind: DiffLineKind) -> usize {
+        self.lines.iter().filter(|l| l.kind == kind).count()
+    }
+}
+
+/// Find the newest file-editing `tool_use` that has no `tool_result` yet.
+/// `previous` returns the current contents of a file a `Write` would
+/// replace, when they can be read.
+pub fn pending_edit(
+    records: &[TranscriptRecord],
+    previous: impl Fn(&str) -> Option<String>,
+) -> Option<PendingEdit> {
+    for record in records.iter().rev() {
+        match record {
+            TranscriptRecord::ToolResult { .. } => return None,
+            TranscriptRecord::ToolUse {
+                tool_name,
+                input_full,
+                ..
+            } => return edit_from_input(tool_name, input_full.as_ref()?, previous),
+            _ => continue,
+        }
+    }
+    None
+}
+
+/// Current contents of `file_path`, resolved against `cwd` when relative.
+/// `None` when unreadable, or relative with no `cwd` to resolve it in.
+pub fn read_previous(cwd: &str, file_path: &str) -> Option<String> {
+    let path = Path::new(file_path);
+    if path.is_relative() && cwd.is_empty() {
+        return None;
+    }
+    std::fs::read_to_string(Path::new(cwd).join(path)).ok()
+}
+
+/// Hunk header for a `Write` whose previous file couldn't be read.
+const PREVIOUS_NOT_SHOWN: &str = "@@ new contents (previous file not shown) @@";
+
+fn edit_from_input(
+    tool_name: &str,
+    input: &Value,
+    previous: impl Fn(&str) -> Option<String>,
+) -> Option<PendingEdit> {
+    let str_field = |v: &Value, key: &str| v.get(key).and_then(Value::as_str).map(str::to_string);
+    let file_path = str_field(input, "file_path")?;
+    let mut lines = vec![
+        DiffLine {
+            kind: DiffLineKind::Header,
+            text: format!("--- a/{file_path}"),
+        },
+        DiffLine {
+            kind: DiffLineKind::Header,
+            text: format!("+++ b/{file_path}"),
+        },
+    ];
+    let pairs: Vec<(String, String)> = match tool_name {
+        "Edit" => vec![(
+            str_field(input, "old_string").unwrap_or_default(),
+            str_field(input, "new_string").unwrap_or_default(),
+        )],
+        "MultiEdit" => input
+            .get("edits")?
+            .as_array()?
+            .iter()
+            .map(|e| {
+                (
+                    str_field(e, "old_string").unwrap_or_default(),
+                    str_field(e, "new_string").unwrap_or_default(),
+                )
+            })
+            .collect(),
+        "Write" => {
+            let content = str_field(input, "content").unwrap_or_default();
+            match previous(&file_path) {
+                Some(old) => lines.extend(diff_lines(&old, &content, false)),
+                None => {
+                    lines.push(DiffLine {
+                        kind: DiffLineKind::Hunk,
+                        text: PREVIOUS_NOT_SHOWN.into(),
+                    });
+                    lines.extend(content.lines().map(|l| DiffLine {
+                        kind: DiffLineKind::Added,
+                        text: format!("+{l}"),
+                    }));
+                }
+            }
+            return Some(PendingEdit {
+                tool_name: tool_name.to_string(),
+                file_path,
+                lines,
+            });
+        }
+        _ => return None,
+    };
+
+    for (i, (old, new)) in pairs.iter().enumerate() {
+        if pairs.len() > 1 {
+            lines.push(DiffLine {
+                kind: DiffLineKind::Hunk,
+                text: format!("@@ edit {}/{} @@", i + 1, pairs.len()),
+            });
+        }
+        lines.extend(diff_lines(old, new, true));
+    }
+    Some(PendingEdit {
+        tool_name: tool_name.to_string(),
+        file_path,
+        lines,
+    })
+}
+
+/// Unified-diff body for one old → new replacement. With `snippet` the
+/// hunk headers say their line numbers are relative to the snippet.
+pub fn diff_lines(old: &str, new: &str, snippet: bool) -> Vec<DiffLine> {
+    let diff = TextDiff::from_lines(old, new);
+    let mut out = Vec::new();
+    for group in diff.grouped_ops(CONTEXT) {
+        let (first, last) = (&group[0], &group[group.len() - 1]);
+        let old_range = first.old_range().start..last.old_range().end;
+        let new_range = first.new_range().start..last.new_range().end;
+        let mut header = format!(
+            "@@ -{},{} +{},{} @@",
+            old_range.start + 1,
+            old_range.len(),
+            new_range.start + 1,
+            new_range.len()
+        );
+        if snippet {
+            header.push_str(" snippet lines, not file lines");
+        }
+        out.push(DiffLine {
+            kind: DiffLineKind::Hunk,
+            text: header,
+        });
+        for op in &group {
+            for change in diff.iter_changes(op) {
+                let (kind, sign) = match change.tag() {
+                    ChangeTag::Equal => (DiffLineKind::Context, ' '),
+                    ChangeTag::Insert => (DiffLineKind::Added, '+'),
+                    ChangeTag::Delete => (DiffLineKind::Removed, '-'),
+                };
+                let text = change.value().trim_end_matches(['\n', '\r']);
+                out.push(DiffLine {
+                    kind,
+                    text: format!("{sign}{text}"),
+                });
+            }
+        }
+    }
+    out
+}
+
+#[derive(Debug, Clone)]
+pub struct DiffView {
+    pub agent_id: String,
+    pub edit: PendingEdit,
+    scroll: usize,
+}
+
+impl DiffView {
+    pub fn new(agent_id: String, edit: PendingEdit) -> Self {
+        Self {
+            agent_id,
+            edit,
+            scroll: 0,
+        }
+    }
+
+    pub fn scroll_down(&mut self, n: usize) {
+        let max = self.edit.lines.len().saturating_sub(1);
+        self.scroll = (self.scroll + n).min(max);
+    }
+
+    pub fn scroll_up(&mut self, n: usize) {
+        self.scroll = self.scroll.saturating_sub(n);
+    }
+}
+
+fn line_style(kind: DiffLineKind) -> Style {
+    let base = Style::default();
+    match kind {
+        DiffLineKind::Header => base.add_modifier(Modifier::BOLD),
+        DiffLineKind::Hunk => base.fg(Color::Cyan),
+        DiffLineKind::Context => base,
+        DiffLineKind::Added => base.fg(Color::Green),
+        DiffLineKind::Removed => base.fg(Color::Red),
+    }
+}
+
+pub fn render(frame: &mut Frame, area: Rect, view: &DiffView, status_line: &str) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Min(1),    // diff
+            Constraint::Length(3), // hint box
+            Constraint::Length(1), // status
+        ])
+        .split(area);
+
+    let lines: Vec<Line> = view
+        .edit
+        .lines
+        .iter()
+        .skip(view.scroll)
+        .map(|l| Line::from(Span::styled(l.text.clone(), line_style(l.kind))))
+        .collect();
+    let title = format!(
+        " {} {} — {} (+{} -{}) ",
+        view.edit.tool_name,
+        view.edit.file_path,
+        view.agent_id,
+        view.edit.added(),
+        view.edit.removed()
+    );
+    let block = Block::default().borders(Borders::ALL).title(title);
+    frame.render_widget(Paragraph::new(lines).block(block), chunks[0]);
+
+    let hint = Paragraph::new("j/k scroll  PgUp/PgDn page  a approve  n reject  Esc close")
+        .block(Block::default().borders(Borders::ALL).title(" keys "));
+    frame.render_widget(hint, chunks[1]);
+
+    let status =
+        Paragraph::new(status_line.to_string()).style(Style::default().fg(Color::DarkGray));
+    frame.render_widget(status, chunks[2]);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn none(_: &str) -> Option<String> {
+        None
+    }
+
+    fn tool_use(name: &str, input: Value) -> TranscriptRecord {
+        TranscriptRecord::ToolUse {
+            tool_name: name.into(),
+            input_summary: String::new(),
+            input_full: Some(input),
+            timestamp: None,
+        }
+    }
+
+    #[test]
+    fn edit_becomes_unified_diff() {
+        let records = [tool_use(
+            "Edit",
+            json!({
+                "file_path": "src/lib.rs",
+                "old_string": "fn a() {}\nfn b() {}\n",
+                "new_string": "fn a() {}\nfn c() {}\n",
+            }),
+        )];
+        let edit = pending_edit(&records, none).unwrap();
+        assert_eq!(edit.file_path, "src/lib.rs");
+        assert_eq!((edit.added(), edit.removed()), (1, 1));
+        let texts: Vec<_> = edit.lines.iter().map(|l| l.text.as_str()).collect();
+        assert_eq!(
+            texts,
+            vec![
+                "--- a/src/lib.rs",
+                "+++ b/src/lib.rs",
+                "@@ -1,2 +1,2 @@ snippet lines, not file lines",
+                " fn a() {}",
+                "-fn b() {}",
+                "+fn c() {}",
+            ]
+        );
+    }
+
+    #[test]
+    fn write_is_all_additions() {
+        let records = [tool_use(
+            "Write",
+            json!({"file_path": "new.txt", "content": "one\ntwo\n"}),
+        )];
+        let edit = pending_edit(&records, none).unwrap();
+        assert_eq!((edit.added(), edit.removed()), (2, 0));
+        // No made-up line numbers when the old file is unknown.
+        assert_eq!(edit.lines[2].text, PREVIOUS_NOT_SHOWN);
+    }
+
+    #[test]
+    fn write_over_existing_file_shows_removals() {
+        let records = [tool_use(
+            "Write",
+            json!({"file_path": "f.txt", "content": "one\nthree\n"}),
+        )];
+        let edit = pending_edit(&records, |path| {
+            (path == "f.txt").then(|| "one\ntwo\n".to_string())
+        })
+        .unwrap();
+        assert_eq!((edit.added(), edit.removed()), (1, 1));
+        assert_eq!(edit.lines[2].text, "@@ -1,2 +1,2 @@");
+        assert!(edit.lines.iter().any(|l| l.text == "-two"));
+    }
+
+    #[test]
+    fn multi_edit_labels_each_edit() {
+        let records = [tool_use(
+            "MultiEdit",
+            json!({"file_path": "f", "edits": [
+                {"old_string": "a", "new_string": "b"},
+                {"old_string": "c", "new_string": "d"},
+            ]}),
+        )];
+        let edit = pending_edit(&records, none).unwrap();
+        assert!(edit.lines.iter().any(|l| l.text == "@@ edit 2/2 @@"));
+        assert_eq!(edit.added(), 2);
+    }
+
+    #[test]
+    fn resolved_or_non_edit_tool_is_not_pending() {
+        let resolved = [
+            tool_use(
+                "Edit",
+                json!({"file_path": "f", "old_string": "a", "new_string": "b"}),
+            ),
+            TranscriptRecord::ToolResult {
+                output_summary: "ok".into(),
+                is_error: false,
+                timestamp: None,
+            },
+        ];
+        assert!(pending_edit(&resolved, none).is_none());
+        assert!(pending_edit(&[tool_use("Bash", json!({"command": "ls"}))], none).is_none());
+        assert!(pending_edit(&[], none).is_none());
+    }
+}
@@ -0,0 +1,115 @@
+//! Time each agent has spent in its current attention state.
+//!
+//! `AgentSnapshot` has no state timestamp, so the clock is kept here:
+//! every snapshot is compared with the last one and an agent's timer
+//! restarts when its attention changes. Agents already present when the
+//! client started have been in their state for *at least* the measured
+//! time; those readings are marked inexact and shown as `>12m`.
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use crate::types::{attention_label, AgentSnapshot};
+
+#[derive(Debug, Clone, Copy)]
+struct Entry {
+    state: &'static str,
+    since: Instant,
+    /// The state change itself was observed.
+    exact: bool,
+}
+
+#[derive(Debug, Default)]
+pub struct StateClock {
+    entries: HashMap<String, Entry>,
+    /// At least one snapshot has been seen; agents appearing after that
+    /// are new, so their start time is exact.
+    primed: bool,
+}
+
+impl StateClock {
+    /// Fold a new snapshot in. Agents that disappeared are forgotten.
+    pub fn observe(&mut self, agents: &[AgentSnapshot], now: Instant) {
+        let mut next = HashMap::with_capacity(agents.len());
+        for a in agents {
+            let state = attention_label(a.attention.as_ref());
+            let entry = match self.entries.get(&a.id) {
+                Some(e) if e.state == state => *e,
+                Some(_) => Entry {
+                    state,
+                    since: now,
+                    exact: true,
+                },
+                None => Entry {
+                    state,
+                    since: now,
+                    exact: self.primed,
+                },
+            };
+            next.insert(a.id.clone(), entry);
+        }
+        self.entries = next;
+        self.primed = true;
+    }
+
+    /// Time in the current state and whether that is exact.
+    pub fn elapsed(&self, id: &str, now: Instant) -> Option<(Duration, bool)> {
+        self.entries
+            .get(id)
+            .map(|e| (now.saturating_duration_since(e.since), e.exact))
+    }
+
+    /// Row label: `42s`, `12m`, `3h04m`, `2d`; inexact ones get `>`.
+    pub fn label(&self, id: &str, now: Instant) -> Option<String> {
+        let (d, exact) = self.elapsed(id, now)?;
+        let prefix = if exact { "" } else { ">" };
+        Some(format!("{prefix}{}", compact(d)))
+    }
+}
+
+fn compact(d: Duration) -> String {
+    let secs = d.as_secs();
+    match secs {
+        0..=59 => format!("{secs}s"),
+        60..=3599 => format!("{}m", secs / 60),
+        3600..=86399 => format!("{}h{:02}m", secs / 3600, secs % 3600 / 60),
+        _ => format!("{}d", secs / 86400),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::test_support::agent;
+
+    #[test]
+    fn timer_restarts_on_state_change_only() {
+        let mut clock = StateClock::default();
+        let t0 = Instant::now();
+        clock.observe(&[agent("a", None)], t0);
+        assert_eq!(
+            clock.label("a", t0 + Duration::from_secs(90)).as_deref(),
+            Some(">1m")
+        );
+
+        let t1 = t0 + Duration::from_secs(100);
+        clock.observe(&[agent("a", None), agent("b", None)], t1);
+        // Same state: keeps counting from t0, still inexact.
+        assert_eq!(
+            clock.elapsed("a", t1),
+            Some((Duration::from_secs(100), false))
+        );
+        // Appeared after the first snapshot: exact.
+        assert_eq!(clock.elapsed("b", t1), Some((Duration::ZERO, true)));
+
+        let t2 = t1 + Duration::from_secs(5);
+        clock.observe(&[agent("a", Some("halted"))], t2);
+        assert_eq!(
+            clock
+                .label("a", t2 + Duration::from_secs(3 * 3600 + 240))
+                .as_deref(),
+            Some("3h04m")
+        );
+        assert!(clock.elapsed("b", t2).is_none());
+    }
+}
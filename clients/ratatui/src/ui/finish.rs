@@ -0,0 +1,197 @@
+//! "Finish and exit" for an agent.
+//!
+//! Killing a pane loses whatever the agent was in the middle of. Finishing
+//! instead sends [`WRAP_UP_PROMPT`], waits for the agent to work through
+//! it and come back to rest, saves the transcript (the agent's closing
+//! summary included) and only then kills the pane. [`Finishing`] tracks
+//! the agents in that sequence across snapshots.
+//!
+//! tmai-core queues the prompt when the agent is busy. The pane must not
+//! be killed when that in-flight turn ends, so a queued wrap-up first
+//! waits for the agent to rest, which is when the prompt is delivered,
+//! and only then for the wrap-up turn itself.
+//!
+//! A wrap-up turn that starts and ends between two snapshots is never
+//! seen running, so an agent still waiting to pick the prompt up after
+//! [`SENT_WINDOW`] is reported and dropped rather than waited on forever.
+//! Its pane is left alone: killing it on a guess could lose the work the
+//! prompt asked to save.
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use crate::types::{AgentAttention, AgentSnapshot, TranscriptRecord};
+
+pub const WRAP_UP_PROMPT: &str = "Wrap up now: commit or stash your work in progress, then reply \
+     with a short summary of what you did and what is left. Don't start anything new.";
+
+/// How long a delivered wrap-up prompt may go without the agent being
+/// seen working on it.
+pub const SENT_WINDOW: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Stage {
+    /// Prompt queued behind the turn in flight, which hasn't ended yet.
+    Queued,
+    /// Prompt delivered; the agent hasn't picked it up yet.
+    Sent,
+    /// The agent was seen running on the prompt.
+    Working,
+}
+
+#[derive(Debug, Default)]
+pub struct Finishing {
+    /// Agent id → stage and when it was entered.
+    agents: HashMap<String, (Stage, Instant)>,
+}
+
+impl Finishing {
+    /// Track `id` after the wrap-up prompt was sent; `queued` when
+    /// tmai-core held it back because the agent was busy.
+    pub fn start(&mut self, id: &str, queued: bool, now: Instant) {
+        let stage = if queued { Stage::Queued } else { Stage::Sent };
+        self.agents.insert(id.to_string(), (stage, now));
+    }
+
+    pub fn contains(&self, id: &str) -> bool {
+        self.agents.contains_key(id)
+    }
+
+    /// Fold a snapshot in and return the agents that have finished their
+    /// wrap-up turn; they are no longer tracked. Agents that vanished are
+    /// dropped. A halted agent (waiting on an approval) keeps waiting.
+    pub fn observe(&mut self, agents: &[AgentSnapshot], now: Instant) -> Vec<String> {
+        let mut done = Vec::new();
+        self.agents.retain(|id, (stage, since)| {
+            let Some(agent) = agents.iter().find(|a| a.id == *id) else {
+                return false;
+            };
+            let resting = matches!(
+                agent.attention,
+                Some(AgentAttention::completed | AgentAttention::started)
+            );
+            match (resting, agent.attention.is_none(), *stage) {
+                // The turn in flight ended; tmai-core delivers the prompt now.
+                (true, _, Stage::Queued) => (*stage, *since) = (Stage::Sent, now),
+                (_, true, Stage::Sent) => (*stage, *since) = (Stage::Working, now),
+                (true, _, Stage::Working) => {
+                    done.push(id.clone());
+                    return false;
+                }
+                _ => {}
+            }
+            true
+        });
+        done.sort();
+        done
+    }
+
+    /// Agents whose delivered prompt hasn't been seen running for
+    /// [`SENT_WINDOW`]; they are no longer tracked.
+    pub fn stuck(&mut self, now: Instant) -> Vec<String> {
+        let mut stuck = Vec::new();
+        self.agents.retain(|id, (stage, since)| {
+            if *stage == Stage::Sent && now.duration_since(*since) >= SENT_WINDOW {
+                stuck.push(id.clone());
+                return false;
+            }
+            true
+        });
+        stuck.sort();
+        stuck
+    }
+}
+
+/// First line of the agent's last reply, for the status line.
+pub fn summary_line(records: &[TranscriptRecord]) -> Option<&str> {
+    records.iter().rev().find_map(|r| match r {
+        TranscriptRecord::AssistantText { text, .. } => {
+            text.lines().map(str::trim).find(|l| !l.is_empty())
+        }
+        _ => None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::test_support::agent;
+
+    #[test]
+    fn done_only_after_working_then_resting() {
+        let t0 = Instant::now();
+        let mut f = Finishing::default();
+        f.start("a", false, t0);
+        f.start("gone", false, t0);
+        // Still resting from before the prompt: not done yet.
+        assert!(f.observe(&[agent("a", Some("completed"))], t0).is_empty());
+        assert!(!f.contains("gone"));
+        assert!(f.observe(&[agent("a", None)], t0).is_empty());
+        assert!(f.observe(&[agent("a", Some("halted"))], t0).is_empty());
+        assert_eq!(f.observe(&[agent("a", Some("completed"))], t0), ["a"]);
+        assert!(!f.contains("a"));
+    }
+
+    #[test]
+    fn queued_prompt_waits_for_the_turn_in_flight() {
+        let t0 = Instant::now();
+        let mut f = Finishing::default();
+        f.start("a", true, t0);
+        // The turn that was running when the prompt was queued ends.
+        assert!(f.observe(&[agent("a", None)], t0).is_empty());
+        assert!(f.observe(&[agent("a", Some("completed"))], t0).is_empty());
+        // Now the wrap-up runs and ends.
+        assert!(f.observe(&[agent("a", None)], t0).is_empty());
+        assert_eq!(f.observe(&[agent("a", Some("completed"))], t0), ["a"]);
+    }
+
+    #[test]
+    fn unseen_wrap_up_turn_is_reported_after_window() {
+        let t0 = Instant::now();
+        let mut f = Finishing::default();
+        f.start("a", false, t0);
+        f.start("b", true, t0);
+        f.start("c", false, t0);
+        // c is seen working, so it isn't stuck however long it takes.
+        let agents = [
+            agent("a", Some("completed")),
+            agent("b", None),
+            agent("c", None),
+        ];
+        f.observe(&agents, t0);
+        assert!(f.stuck(t0 + SENT_WINDOW / 2).is_empty());
+
+        // b's queued prompt is delivered late; its window starts then.
+        let later = t0 + SENT_WINDOW / 2;
+        let agents = [
+            agent("a", Some("completed")),
+            agent("b", Some("completed")),
+            agent("c", None),
+        ];
+        f.observe(&agents, later);
+        assert_eq!(f.stuck(t0 + SENT_WINDOW), ["a"]);
+        assert!(!f.contains("a"));
+        assert!(f.contains("c"));
+        assert_eq!(f.stuck(later + SENT_WINDOW), ["b"]);
+    }
+
+    #[test]
+    fn summary_is_first_line_of_last_reply() {
+        let records = vec![
+            TranscriptRecord::AssistantText {
+                text: "earlier".into(),
+                timestamp: None,
+            },
+            TranscriptRecord::AssistantText {
+                text: "\n  Committed the parser fix.\nTests pass.".into(),
+                timestamp: None,
+            },
+            TranscriptRecord::User {
+                text: "thanks".into(),
+                timestamp: None,
+            },
+        ];
+        assert_eq!(summary_line(&records), Some("Committed the parser fix."));
+        assert_eq!(summary_line(&[]), None);
+    }
+}
@@ -0,0 +1,211 @@
+//! Git detail line for the selected agent.
+//!
+//! `AgentSnapshot` carries only the branch name and dirty flag; ahead /
+//! behind counts come from `GET /api/git/branches`, which is too heavy to
+//! call on every redraw. [`GitInfoCache`] keeps one response per repo for
+//! [`TTL`] — failures included, so an engine without the endpoint isn't
+//! asked again on every tick. The worktree list (for the agent's diff
+//! size) is refreshed on the same interval.
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use crate::types::{AgentSnapshot, BranchListResponse, WorktreeSnapshot};
+use crate::ui::worktrees;
+
+/// How long a `/git/branches` or `/worktrees` response is reused.
+pub const TTL: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Default)]
+pub struct GitInfoCache {
+    entries: HashMap<String, (Instant, Option<BranchListResponse>)>,
+    worktrees: Option<(Instant, Vec<WorktreeSnapshot>)>,
+}
+
+impl GitInfoCache {
+    /// Whether `repo` has no entry younger than [`TTL`].
+    pub fn needs_refresh(&self, repo: &str, now: Instant) -> bool {
+        self.entries
+            .get(repo)
+            .is_none_or(|(at, _)| now.duration_since(*at) >= TTL)
+    }
+
+    pub fn insert(&mut self, repo: String, now: Instant, info: Option<BranchListResponse>) {
+        self.entries.insert(repo, (now, info));
+    }
+
+    /// Stamp `repo` as fetched at `now` while a request is in flight, so
+    /// it isn't asked for again meanwhile. Any older response stays
+    /// visible until [`Self::insert`] replaces it.
+    pub fn touch(&mut self, repo: &str, now: Instant) {
+        let info = self.entries.remove(repo).and_then(|(_, info)| info);
+        self.entries.insert(repo.to_string(), (now, info));
+    }
+
+    pub fn get(&self, repo: &str) -> Option<&BranchListResponse> {
+        self.entries.get(repo).and_then(|(_, info)| info.as_ref())
+    }
+
+    pub fn worktrees_need_refresh(&self, now: Instant) -> bool {
+        self.worktrees
+            .as_ref()
+            .is_none_or(|(at, _)| now.duration_since(*at) >= TTL)
+    }
+
+    pub fn set_worktrees(&mut self, now: Instant, list: Vec<WorktreeSnapshot>) {
+        self.worktrees = Some((now, list));
+    }
+
+    /// [`Self::touch`] for the worktree list.
+    pub fn touch_worktrees(&mut self, now: Instant) {
+        let list = self.worktrees.take().map(|(_, list)| list);
+        self.worktrees = Some((now, list.unwrap_or_default()));
+    }
+
+    /// The worktree `agent` runs in: the one tmai-core attached it to, or
+    /// failing that the one whose path is the agent's cwd.
+    pub fn worktree_of(&self, agent: &AgentSnapshot) -> Option<&WorktreeSnapshot> {
+        let (_, list) = self.worktrees.as_ref()?;
+        list.iter()
+            .find(|w| {
+                worktrees::agent_target(w).is_some_and(|t| t == agent.id || t == agent.target)
+            })
+            .or_else(|| list.iter().find(|w| w.path == agent.cwd))
+    }
+}
+
+/// One-line summary, e.g.
+/// `feat-x* · ↑2 ↓0 origin/feat-x · 3 ahead of main · +12 -3 (2 files)`.
+/// `None` when the agent isn't in a git repo.
+pub fn summary(
+    agent: &AgentSnapshot,
+    branches: Option<&BranchListResponse>,
+    worktree: Option<&WorktreeSnapshot>,
+) -> Option<String> {
+    let branch = agent.git_branch.as_deref()?;
+    let dirty = if agent.git_dirty == Some(true) {
+        "*"
+    } else {
+        ""
+    };
+    let mut parts = vec![format!("{branch}{dirty}")];
+    if let Some(b) = branches {
+        match b.remote_tracking.get(branch) {
+            Some(t) => parts.push(format!("↑{} ↓{} {}", t.ahead, t.behind, t.remote_branch)),
+            None if branch != b.default_branch => parts.push("no upstream".into()),
+            None => {}
+        }
+        if let Some((ahead, behind)) = b.ahead_behind.get(branch) {
+            let base = b
+                .parents
+                .get(branch)
+                .map(String::as_str)
+                .unwrap_or(&b.default_branch);
+            match (ahead, behind) {
+                (0, 0) => {}
+                (a, 0) => parts.push(format!("{a} ahead of {base}")),
+                (0, n) => parts.push(format!("{n} behind {base}")),
+                (a, n) => parts.push(format!("{a} ahead, {n} behind {base}")),
+            }
+        }
+    }
+    if let Some(diff) = worktree.and_then(worktrees::diff_label) {
+        parts.push(diff);
+    }
+    Some(parts.join(" · "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{test_support, RemoteTracking};
+
+    fn agent(branch: Option<&str>, dirty: bool) -> AgentSnapshot {
+        let mut a = test_support::agent("x", None);
+        a.git_branch = branch.map(str::to_string);
+        a.git_dirty = Some(dirty);
+        a
+    }
+
+    #[test]
+    fn summary_combines_upstream_and_base_counts() {
+        let mut b = BranchListResponse {
+            default_branch: "main".into(),
+            ..Default::default()
+        };
+        b.remote_tracking.insert(
+            "feat".into(),
+            RemoteTracking {
+                remote_branch: "origin/feat".into(),
+                ahead: 2,
+                behind: 0,
+            },
+        );
+        b.ahead_behind.insert("feat".into(), (12, 1));
+        assert_eq!(
+            summary(&agent(Some("feat"), true), Some(&b), None).as_deref(),
+            Some("feat* · ↑2 ↓0 origin/feat · 12 ahead, 1 behind main")
+        );
+        assert_eq!(
+            summary(&agent(Some("local"), false), Some(&b), None).as_deref(),
+            Some("local · no upstream")
+        );
+        assert_eq!(
+            summary(&agent(Some("feat"), false), None, None).as_deref(),
+            Some("feat")
+        );
+        assert!(summary(&agent(None, false), Some(&b), None).is_none());
+    }
+
+    #[test]
+    fn worktree_diff_is_matched_by_agent_or_path() {
+        let mut c = GitInfoCache::default();
+        let wt = |name: &str, target: Option<&str>| -> WorktreeSnapshot {
+            serde_json::from_value(serde_json::json!({
+                "name": name, "path": format!("/r/{name}"), "repo_name": "r",
+                "repo_path": "/r", "is_main": false, "agent_target": target,
+                "diff_summary": {"files_changed": 1, "insertions": 4, "deletions": 0}
+            }))
+            .unwrap()
+        };
+        c.set_worktrees(Instant::now(), vec![wt("a", Some("x")), wt("b", None)]);
+        let mut a = agent(Some("feat"), false);
+        assert_eq!(c.worktree_of(&a).unwrap().name, "a");
+        assert_eq!(
+            summary(&a, None, c.worktree_of(&a)).as_deref(),
+            Some("feat · +4 -0 (1 file)")
+        );
+        a.id = "y".into();
+        a.target = "y".into();
+        a.cwd = "/r/b".into();
+        assert_eq!(c.worktree_of(&a).unwrap().name, "b");
+    }
+
+    #[test]
+    fn cache_expires_after_ttl() {
+        let mut c = GitInfoCache::default();
+        let t0 = Instant::now();
+        assert!(c.needs_refresh("/r", t0));
+        c.insert("/r".into(), t0, None);
+        assert!(!c.needs_refresh("/r", t0 + Duration::from_secs(1)));
+        assert!(c.needs_refresh("/r", t0 + TTL));
+    }
+
+    #[test]
+    fn touch_keeps_old_info_and_defers_refetch() {
+        let mut c = GitInfoCache::default();
+        let t0 = Instant::now();
+        let info = BranchListResponse {
+            default_branch: "main".into(),
+            ..Default::default()
+        };
+        c.insert("/r".into(), t0, Some(info));
+        let t1 = t0 + TTL;
+        c.touch("/r", t1);
+        assert!(!c.needs_refresh("/r", t1));
+        assert_eq!(c.get("/r").unwrap().default_branch, "main");
+
+        c.touch_worktrees(t1);
+        assert!(!c.worktrees_need_refresh(t1));
+    }
+}
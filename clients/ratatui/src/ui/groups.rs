@@ -0,0 +1,260 @@
+//! Project grouping for the agent list.
+//!
+//! Agents are grouped by their unit (tmai-core `[[unit]]`) when the engine
+//! serves one, otherwise by repository (`git_common_dir`), mirroring the
+//! React sidebar's `groupByProject`: repositories are keyed by their full
+//! directory and shown by its name. Each group carries a roll-up of its
+//! members' attention so a folded header still says whether anything in
+//! it needs the user. Pinned agents are pulled out into a leading group
+//! of their own so they stay at the top whatever their project.
+
+use std::fmt;
+use std::ops::Range;
+
+use crate::pins::Pins;
+use crate::types::{AgentAttention, AgentSnapshot};
+
+/// Identity of a group. Variants keep the namespaces apart, so a unit
+/// named `app`, two repos both called `app` and a project called `pinned`
+/// all stay separate groups.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum GroupKey {
+    Pinned,
+    /// tmai-core `[[unit]]` name.
+    Unit(String),
+    /// Repository directory, `git_common_dir` without the trailing `/.git`.
+    Repo(String),
+    /// Agents outside any repository.
+    NoProject,
+}
+
+impl GroupKey {
+    /// Header text: the unit name or the repository's directory name.
+    pub fn label(&self) -> &str {
+        match self {
+            GroupKey::Pinned => "pinned",
+            GroupKey::Unit(name) => name,
+            GroupKey::Repo(dir) => dir.rsplit('/').find(|s| !s.is_empty()).unwrap_or(dir),
+            GroupKey::NoProject => "(no project)",
+        }
+    }
+}
+
+impl fmt::Display for GroupKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.label())
+    }
+}
+
+/// Project an agent belongs to: its unit, else its repository directory
+/// (`/src/app/.git` → `/src/app`).
+pub fn project_key(agent: &AgentSnapshot) -> GroupKey {
+    if let Some(unit) = agent.unit.as_deref().filter(|u| !u.is_empty()) {
+        return GroupKey::Unit(unit.to_string());
+    }
+    agent
+        .git_common_dir
+        .as_deref()
+        .map(normalize_git_dir)
+        .filter(|dir| !dir.is_empty())
+        .map_or(GroupKey::NoProject, |dir| GroupKey::Repo(dir.to_string()))
+}
+
+/// Strip a trailing `/.git` and slashes, as React's `normalizeGitDir`.
+fn normalize_git_dir(dir: &str) -> &str {
+    let dir = dir
+        .strip_suffix("/.git/")
+        .or_else(|| dir.strip_suffix("/.git"))
+        .unwrap_or(dir);
+    dir.trim_end_matches('/')
+}
+
+fn group_key(agent: &AgentSnapshot, pins: &Pins) -> GroupKey {
+    if pins.contains(&agent.id) {
+        GroupKey::Pinned
+    } else {
+        project_key(agent)
+    }
+}
+
+/// Reorder `agents` so each group's members are contiguous: pinned
+/// agents first, then projects in the order of their first member.
+/// Members keep their relative order.
+pub fn sort_by_project(agents: &mut [AgentSnapshot], pins: &Pins) {
+    let mut order: Vec<GroupKey> = vec![GroupKey::Pinned];
+    for a in agents.iter() {
+        let key = group_key(a, pins);
+        if !order.contains(&key) {
+            order.push(key);
+        }
+    }
+    agents.sort_by_cached_key(|a| {
+        let key = group_key(a, pins);
+        order.iter().position(|k| *k == key)
+    });
+}
+
+/// Attention counts for one group.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Rollup {
+    pub halted: usize,
+    pub started: usize,
+    pub running: usize,
+    pub done: usize,
+}
+
+impl Rollup {
+    /// Most urgent state present; halted beats started beats running.
+    pub fn worst(&self) -> Option<AgentAttention> {
+        if self.halted > 0 {
+            Some(AgentAttention::halted)
+        } else if self.started > 0 {
+            Some(AgentAttention::started)
+        } else if self.running > 0 {
+            None
+        } else if self.done > 0 {
+            Some(AgentAttention::completed)
+        } else {
+            None
+        }
+    }
+
+    /// `1 halted, 2 running, 3 done` — most urgent first, zeros omitted.
+    pub fn label(&self) -> String {
+        [
+            (self.halted, "halted"),
+            (self.started, "started"),
+            (self.running, "running"),
+            (self.done, "done"),
+        ]
+        .iter()
+        .filter(|(n, _)| *n > 0)
+        .map(|(n, what)| format!("{n} {what}"))
+        .collect::<Vec<_>>()
+        .join(", ")
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Group {
+    pub key: GroupKey,
+    /// Members, as indices into the (project-sorted) agent slice.
+    pub range: Range<usize>,
+    pub rollup: Rollup,
+}
+
+/// Split a slice already ordered by [`sort_by_project`] into groups.
+pub fn groups(agents: &[AgentSnapshot], pins: &Pins) -> Vec<Group> {
+    let mut out: Vec<Group> = Vec::new();
+    for (i, agent) in agents.iter().enumerate() {
+        let key = group_key(agent, pins);
+        match out.last_mut() {
+            Some(g) if g.key == key => g.range.end = i + 1,
+            _ => out.push(Group {
+                key,
+                range: i..i + 1,
+                rollup: Rollup::default(),
+            }),
+        }
+        let rollup = &mut out.last_mut().expect("just pushed").rollup;
+        match agent.attention {
+            Some(AgentAttention::halted) => rollup.halted += 1,
+            Some(AgentAttention::started) => rollup.started += 1,
+            Some(AgentAttention::completed) => rollup.done += 1,
+            None => rollup.running += 1,
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::test_support::agent;
+
+    fn in_repo(id: &str, common_dir: Option<&str>, attention: Option<&str>) -> AgentSnapshot {
+        let mut a = agent(id, attention);
+        a.git_common_dir = common_dir.map(str::to_string);
+        a
+    }
+
+    #[test]
+    fn key_prefers_unit_then_repo_dir() {
+        let mut a = in_repo("a", Some("/src/myapp/.git/"), None);
+        assert_eq!(project_key(&a), GroupKey::Repo("/src/myapp".into()));
+        assert_eq!(project_key(&a).label(), "myapp");
+        a.unit = Some("platform".into());
+        assert_eq!(project_key(&a), GroupKey::Unit("platform".into()));
+        assert_eq!(project_key(&in_repo("b", None, None)), GroupKey::NoProject);
+    }
+
+    #[test]
+    fn repos_with_the_same_name_stay_apart() {
+        let a = project_key(&in_repo("a", Some("/a/app/.git"), None));
+        let b = project_key(&in_repo("b", Some("/b/app/.git"), None));
+        assert_ne!(a, b);
+        assert_eq!((a.label(), b.label()), ("app", "app"));
+    }
+
+    #[test]
+    fn unit_does_not_collide_with_repo_of_same_name() {
+        let repo = project_key(&in_repo("a", Some("app"), None));
+        let mut unit = in_repo("b", Some("/src/other/.git"), None);
+        unit.unit = Some("app".into());
+        assert_ne!(repo, project_key(&unit));
+    }
+
+    #[test]
+    fn project_named_pinned_is_not_the_pinned_group() {
+        let mut agents = vec![
+            in_repo("p", Some("/src/x/.git"), None),
+            in_repo("q", Some("/src/pinned/.git"), None),
+        ];
+        let mut pins = Pins::default();
+        pins.toggle("p").unwrap();
+        sort_by_project(&mut agents, &pins);
+        let g = groups(&agents, &pins);
+        assert_eq!(g.len(), 2);
+        assert_eq!(g[0].key, GroupKey::Pinned);
+        assert_eq!(g[1].key, GroupKey::Repo("/src/pinned".into()));
+        assert_eq!(g[1].key.label(), g[0].key.label());
+    }
+
+    #[test]
+    fn sorting_makes_groups_contiguous_with_rollups() {
+        let mut agents = vec![
+            in_repo("a1", Some("/src/a/.git"), Some("halted")),
+            in_repo("b1", Some("/src/b/.git"), None),
+            in_repo("a2", Some("/src/a/.git"), None),
+            in_repo("a3", Some("/src/a/.git"), None),
+            in_repo("b2", Some("/src/b/.git"), Some("completed")),
+        ];
+        let pins = Pins::default();
+        sort_by_project(&mut agents, &pins);
+        let ids: Vec<_> = agents.iter().map(|a| a.id.as_str()).collect();
+        assert_eq!(ids, ["a1", "a2", "a3", "b1", "b2"]);
+
+        let g = groups(&agents, &pins);
+        assert_eq!(g.len(), 2);
+        assert_eq!((g[0].key.label(), g[0].range.clone()), ("a", 0..3));
+        assert_eq!(g[0].rollup.label(), "1 halted, 2 running");
+        assert!(matches!(g[0].rollup.worst(), Some(AgentAttention::halted)));
+        assert_eq!(g[1].rollup.label(), "1 running, 1 done");
+        assert!(g[1].rollup.worst().is_none());
+    }
+
+    #[test]
+    fn pinned_agents_lead_in_their_own_group() {
+        let mut agents = vec![
+            in_repo("a1", Some("/src/a/.git"), None),
+            in_repo("b1", Some("/src/b/.git"), None),
+        ];
+        let mut pins = Pins::default();
+        pins.toggle("b1").unwrap();
+        sort_by_project(&mut agents, &pins);
+        assert_eq!(agents[0].id, "b1");
+        let g = groups(&agents, &pins);
+        let keys: Vec<_> = g.iter().map(|g| g.key.clone()).collect();
+        assert_eq!(keys, [GroupKey::Pinned, GroupKey::Repo("/src/a".into())]);
+    }
+}
@@ -0,0 +1,74 @@
+//! Key-binding overlay, opened with `?`.
+//!
+//! The footer only has room for [`Action::FOOTER`]; this lists every
+//! action with its current binding, in as many columns as the height
+//! needs. Any key closes it.
+
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph},
+    Frame,
+};
+
+use crate::ui::keymap::{Action, Keymap};
+
+/// One line per action: the binding, then the label.
+fn lines(keymap: &Keymap) -> Vec<Line<'static>> {
+    let hints: Vec<(String, &str)> = Action::ALL
+        .iter()
+        .map(|a| (keymap.hint(*a), a.label()))
+        .collect();
+    let width = hints
+        .iter()
+        .map(|(k, _)| k.chars().count())
+        .max()
+        .unwrap_or(0);
+    hints
+        .into_iter()
+        .map(|(k, label)| {
+            Line::from(vec![
+                Span::styled(
+                    format!(" {k:<width$}  "),
+                    Style::default()
+                        .fg(Color::Cyan)
+                        .add_modifier(Modifier::BOLD),
+                ),
+                Span::raw(label),
+            ])
+        })
+        .collect()
+}
+
+pub fn render(frame: &mut Frame, area: Rect, keymap: &Keymap, status_line: &str) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Min(1),    // bindings
+            Constraint::Length(1), // status
+        ])
+        .split(area);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(" keys (any key closes) ");
+    let inner = block.inner(chunks[0]);
+    frame.render_widget(block, chunks[0]);
+
+    let lines = lines(keymap);
+    let rows = (inner.height as usize).max(1);
+    let columns = lines.len().div_ceil(rows);
+    let cells = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints(vec![Constraint::Ratio(1, columns as u32); columns])
+        .split(inner);
+    for (column, cell) in lines.chunks(rows).zip(cells.iter()) {
+        frame.render_widget(Paragraph::new(column.to_vec()), *cell);
+    }
+
+    frame.render_widget(
+        Paragraph::new(status_line.to_string()).style(Style::default().fg(Color::DarkGray)),
+        chunks[1],
+    );
+}
@@ -0,0 +1,451 @@
+//! Scrollback browser for an agent's history.
+//!
+//! The list screen only shows where each agent stands *now*; this view
+//! fetches the agent's full transcript (`GET /api/agents/{id}/transcript`)
+//! on demand and lets the user page through it and search it, so
+//! investigating why an agent errored doesn't mean leaving the TUI.
+//! Scroll offsets count from the bottom: `0` is the live tail.
+
+use std::cell::Cell;
+
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph},
+    Frame,
+};
+use regex::{Regex, RegexBuilder};
+
+use crate::types::{CtxUsage, TranscriptRecord};
+use crate::ui::stats::{self, TranscriptStats};
+
+/// Visual class of a history line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineKind {
+    User,
+    Assistant,
+    Thinking,
+    Tool,
+    ToolResult,
+    ToolError,
+}
+
+#[derive(Debug, Clone)]
+pub struct HistoryView {
+    pub agent_id: String,
+    lines: Vec<(LineKind, String)>,
+    /// Lines scrolled up from the tail.
+    scroll: usize,
+    /// Text being typed after `/`; `None` when not in search entry.
+    pub search_input: Option<String>,
+    query: Option<String>,
+    matches: Vec<usize>,
+    current_match: Option<usize>,
+    /// Body height seen at the last render; drives paging.
+    viewport: Cell<usize>,
+    stats: TranscriptStats,
+    /// Show the session summary instead of the transcript.
+    pub show_stats: bool,
+    /// The agent's context-window usage, for the stats panel.
+    pub context: Option<CtxUsage>,
+}
+
+impl HistoryView {
+    pub fn new(agent_id: String, records: &[TranscriptRecord]) -> Self {
+        Self {
+            agent_id,
+            lines: flatten(records),
+            scroll: 0,
+            search_input: None,
+            query: None,
+            matches: Vec::new(),
+            current_match: None,
+            viewport: Cell::new(20),
+            stats: TranscriptStats::from_records(records),
+            show_stats: false,
+            context: None,
+        }
+    }
+
+    /// Replace the content (after a refetch), keeping the scroll position
+    /// relative to the tail and re-running the active search.
+    pub fn reload(&mut self, records: &[TranscriptRecord]) {
+        self.lines = flatten(records);
+        self.stats = TranscriptStats::from_records(records);
+        self.scroll = self.scroll.min(self.max_scroll());
+        if let Some(q) = self.query.clone() {
+            self.search(&q);
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.lines.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lines.is_empty()
+    }
+
+    fn max_scroll(&self) -> usize {
+        self.lines.len().saturating_sub(self.viewport.get())
+    }
+
+    /// Scroll towards older lines.
+    pub fn scroll_up(&mut self, n: usize) {
+        self.scroll = (self.scroll + n).min(self.max_scroll());
+    }
+
+    /// Scroll towards the tail.
+    pub fn scroll_down(&mut self, n: usize) {
+        self.scroll = self.scroll.saturating_sub(n);
+    }
+
+    pub fn page(&self) -> usize {
+        self.viewport.get().saturating_sub(1).max(1)
+    }
+
+    pub fn to_top(&mut self) {
+        self.scroll = self.max_scroll();
+    }
+
+    pub fn to_tail(&mut self) {
+        self.scroll = 0;
+    }
+
+    pub fn is_at_tail(&self) -> bool {
+        self.scroll == 0
+    }
+
+    /// Case-insensitive search. Jumps to the newest match, mirroring how
+    /// a `?` search in `less` starts from the bottom of the buffer.
+    pub fn search(&mut self, query: &str) {
+        let needle = search_regex(query);
+        self.matches = match &needle {
+            None => Vec::new(),
+            Some(re) => self
+                .lines
+                .iter()
+                .enumerate()
+                .filter(|(_, (_, text))| re.is_match(text))
+                .map(|(i, _)| i)
+                .collect(),
+        };
+        self.query = needle.is_some().then(|| query.to_string());
+        self.current_match = self.matches.len().checked_sub(1);
+        self.reveal_current();
+    }
+
+    /// Jump to the next older match (wraps).
+    pub fn prev_match(&mut self) {
+        if self.matches.is_empty() {
+            return;
+        }
+        let n = self.matches.len();
+        self.current_match = Some(self.current_match.map_or(n - 1, |i| (i + n - 1) % n));
+        self.reveal_current();
+    }
+
+    /// Jump to the next newer match (wraps).
+    pub fn next_match(&mut self) {
+        if self.matches.is_empty() {
+            return;
+        }
+        let n = self.matches.len();
+        self.current_match = Some(self.current_match.map_or(0, |i| (i + 1) % n));
+        self.reveal_current();
+    }
+
+    /// `(1-based current, total)` for the title bar.
+    pub fn match_position(&self) -> Option<(usize, usize)> {
+        self.current_match.map(|i| (i + 1, self.matches.len()))
+    }
+
+    fn reveal_current(&mut self) {
+        let Some(line) = self.current_match.map(|i| self.matches[i]) else {
+            return;
+        };
+        // Put the match a third of the way down the viewport.
+        let height = self.viewport.get();
+        let top = line.saturating_sub(height / 3);
+        let bottom_gap = self.lines.len().saturating_sub(top + height);
+        self.scroll = bottom_gap.min(self.max_scroll());
+    }
+
+    /// Index of the first visible line for a body of `height` rows.
+    fn top(&self, height: usize) -> usize {
+        self.lines
+            .len()
+            .saturating_sub(height)
+            .saturating_sub(self.scroll)
+    }
+}
+
+/// Turn transcript records into display lines, one per text line.
+fn flatten(records: &[TranscriptRecord]) -> Vec<(LineKind, String)> {
+    let mut out = Vec::new();
+    let mut push = |kind: LineKind, prefix: &str, text: &str| {
+        for (i, line) in text.lines().enumerate() {
+            let lead = if i == 0 { prefix } else { "  " };
+            out.push((kind, format!("{lead}{line}")));
+        }
+    };
+    for record in records {
+        match record {
+            TranscriptRecord::User { text, .. } => push(LineKind::User, "› ", text),
+            TranscriptRecord::AssistantText { text, .. } => push(LineKind::Assistant, "", text),
+            TranscriptRecord::Thinking { text, .. } => push(LineKind::Thinking, "∴ ", text),
+            TranscriptRecord::ToolUse {
+                tool_name,
+                input_summary,
+                ..
+            } => push(
+                LineKind::Tool,
+                "⏺ ",
+                &format!("{tool_name}({input_summary})"),
+            ),
+            TranscriptRecord::ToolResult {
+                output_summary,
+                is_error,
+                ..
+            } => {
+                let kind = if *is_error {
+                    LineKind::ToolError
+                } else {
+                    LineKind::ToolResult
+                };
+                push(kind, "  ⎿ ", output_summary)
+            }
+            TranscriptRecord::Unknown => {}
+        }
+    }
+    out
+}
+
+fn line_style(kind: LineKind) -> Style {
+    let base = Style::default();
+    match kind {
+        LineKind::User => base.fg(Color::Cyan).add_modifier(Modifier::BOLD),
+        LineKind::Assistant => base,
+        LineKind::Thinking => base.fg(Color::DarkGray).add_modifier(Modifier::ITALIC),
+        LineKind::Tool => base.fg(Color::Magenta),
+        LineKind::ToolResult => base.fg(Color::DarkGray),
+        LineKind::ToolError => base.fg(Color::Red),
+    }
+}
+
+pub fn render(frame: &mut Frame, area: Rect, view: &HistoryView, status_line: &str) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Min(1),    // history
+            Constraint::Length(3), // search / hint box
+            Constraint::Length(1), // status
+        ])
+        .split(area);
+
+    if view.show_stats {
+        render_stats(frame, chunks[0], view);
+    } else {
+        render_body(frame, chunks[0], view);
+    }
+
+    let hint = match &view.search_input {
+        Some(buf) => Paragraph::new(format!("/{buf}"))
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(" search (Enter to find, Esc to cancel) "),
+            )
+            .style(Style::default().fg(Color::Yellow)),
+        None => Paragraph::new(
+            "j/k scroll  PgUp/PgDn page  g/G top/tail  / search  n/N older/newer match  s stats  e export  r reload  Esc close",
+        )
+        .block(Block::default().borders(Borders::ALL).title(" keys ")),
+    };
+    frame.render_widget(hint, chunks[1]);
+
+    let status =
+        Paragraph::new(status_line.to_string()).style(Style::default().fg(Color::DarkGray));
+    frame.render_widget(status, chunks[2]);
+}
+
+fn render_body(frame: &mut Frame, body: Rect, view: &HistoryView) {
+    let height = body.height.saturating_sub(2) as usize;
+    view.viewport.set(height.max(1));
+
+    let top = view.top(height);
+    let current_line = view.current_match.map(|i| view.matches[i]);
+    let needle = view.query.as_deref().and_then(search_regex);
+    let lines: Vec<Line> = view
+        .lines
+        .iter()
+        .enumerate()
+        .skip(top)
+        .take(height)
+        .map(|(i, (kind, text))| {
+            let style = line_style(*kind);
+            match &needle {
+                Some(n) if view.matches.binary_search(&i).is_ok() => {
+                    highlight(text, n, style, Some(i) == current_line)
+                }
+                _ => Line::from(Span::styled(text.clone(), style)),
+            }
+        })
+        .collect();
+
+    let position = if view.is_at_tail() {
+        "tail".to_string()
+    } else {
+        format!("{}/{}", top + height.min(view.len()), view.len())
+    };
+    let mut title = format!(" history {} — {position} ", view.agent_id);
+    if let Some((cur, total)) = view.match_position() {
+        title.push_str(&format!("[{cur}/{total}] "));
+    }
+    let block = Block::default().borders(Borders::ALL).title(title);
+    frame.render_widget(Paragraph::new(lines).block(block), body);
+}
+
+fn render_stats(frame: &mut Frame, body: Rect, view: &HistoryView) {
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(format!(" session stats {} ", view.agent_id));
+    let lines: Vec<Line> = view
+        .context
+        .as_ref()
+        .map(stats::context_line)
+        .into_iter()
+        .chain(view.stats.lines())
+        .map(Line::from)
+        .collect();
+    frame.render_widget(Paragraph::new(lines).block(block), body);
+}
+
+/// Case-insensitive literal matcher for `query`; `None` when empty.
+///
+/// Matching runs on the original text, so match offsets are always char
+/// boundaries of it (lower-casing first can shift them).
+fn search_regex(query: &str) -> Option<Regex> {
+    if query.is_empty() {
+        return None;
+    }
+    RegexBuilder::new(&regex::escape(query))
+        .case_insensitive(true)
+        .build()
+        .ok()
+}
+
+/// Split `text` around the matches of `needle`.
+fn highlight(text: &str, needle: &Regex, base: Style, current: bool) -> Line<'static> {
+    let hl = if current {
+        Style::default().fg(Color::Black).bg(Color::Yellow)
+    } else {
+        Style::default().fg(Color::Black).bg(Color::DarkGray)
+    };
+    let mut spans = Vec::new();
+    let mut cursor = 0;
+    for m in needle.find_iter(text) {
+        let (start, end) = (m.start(), m.end());
+        if start > cursor {
+            spans.push(Span::styled(text[cursor..start].to_string(), base));
+        }
+        spans.push(Span::styled(text[start..end].to_string(), hl));
+        cursor = end;
+    }
+    if cursor < text.len() {
+        spans.push(Span::styled(text[cursor..].to_string(), base));
+    }
+    Line::from(spans)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn user(text: &str) -> TranscriptRecord {
+        TranscriptRecord::User {
+            text: text.into(),
+            timestamp: None,
+        }
+    }
+
+    fn view_with(n: usize) -> HistoryView {
+        let records: Vec<_> = (0..n).map(|i| user(&format!("line {i}"))).collect();
+        let v = HistoryView::new("main:0.0".into(), &records);
+        v.viewport.set(10);
+        v
+    }
+
+    #[test]
+    fn multi_line_records_are_split() {
+        let v = HistoryView::new(
+            "a".into(),
+            &[TranscriptRecord::AssistantText {
+                text: "one\ntwo".into(),
+                timestamp: None,
+            }],
+        );
+        assert_eq!(v.len(), 2);
+        assert_eq!(v.lines[1].1, "  two");
+    }
+
+    #[test]
+    fn scroll_is_clamped_to_buffer() {
+        let mut v = view_with(30);
+        v.scroll_up(100);
+        assert_eq!(v.scroll, 20);
+        assert_eq!(v.top(10), 0);
+        v.scroll_down(5);
+        assert_eq!(v.top(10), 5);
+        v.to_tail();
+        assert!(v.is_at_tail());
+        assert_eq!(v.top(10), 20);
+    }
+
+    #[test]
+    fn search_starts_at_newest_match_and_wraps() {
+        let mut v = view_with(30);
+        v.search("LINE 2");
+        // "line 2", "line 20".."line 29" → 11 matches, newest first.
+        assert_eq!(v.match_position(), Some((11, 11)));
+        v.prev_match();
+        assert_eq!(v.match_position(), Some((10, 11)));
+        v.next_match();
+        v.next_match();
+        assert_eq!(v.match_position(), Some((1, 11)));
+        assert_eq!(v.matches[0], 2);
+        // The first match is revealed inside the viewport.
+        let top = v.top(10);
+        assert!(top <= 2 && 2 < top + 10);
+    }
+
+    #[test]
+    fn empty_search_clears_matches() {
+        let mut v = view_with(5);
+        v.search("line");
+        assert!(v.match_position().is_some());
+        v.search("");
+        assert_eq!(v.match_position(), None);
+    }
+
+    #[test]
+    fn highlight_splits_around_matches() {
+        let re = search_regex("FOO").unwrap();
+        let line = highlight("foo Bar foo", &re, Style::default(), false);
+        let parts: Vec<_> = line.spans.iter().map(|s| s.content.to_string()).collect();
+        assert_eq!(parts, vec!["foo", " Bar ", "foo"]);
+    }
+
+    #[test]
+    fn highlight_survives_case_mapping_that_moves_offsets() {
+        // "İİK" (Kelvin sign) lower-cases to the same byte length, but
+        // with the "k" at an offset inside the original Kelvin sign.
+        let text = "İİ\u{212A} end";
+        assert_eq!(text.to_lowercase().len(), text.len());
+        let re = search_regex("k").unwrap();
+        let line = highlight(text, &re, Style::default(), true);
+        let parts: Vec<_> = line.spans.iter().map(|s| s.content.to_string()).collect();
+        assert_eq!(parts, vec!["İİ", "\u{212A}", " end"]);
+    }
+}
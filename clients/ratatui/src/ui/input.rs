@@ -0,0 +1,139 @@
+//! Text box for prompts: multi-line editing and history recall.
+//!
+//! Enter submits; Alt+Enter (or Shift+Enter, on terminals that report it)
+//! starts a new line. Pasted text arrives as one bracketed-paste event and
+//! is inserted as-is, so its newlines don't submit. Up and Down step
+//! through [`PromptHistory`], and stepping past the newest entry brings
+//! back what was being typed.
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+use crate::prompt_history::PromptHistory;
+
+/// What a key did to the input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Edit {
+    Submit,
+    Cancel,
+    Editing,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct PromptInput {
+    text: String,
+    /// History entry shown, as an index for [`PromptHistory::recent`].
+    recall: Option<usize>,
+    /// What was typed before recall started.
+    draft: String,
+}
+
+impl PromptInput {
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn key(&mut self, key: KeyEvent, history: &PromptHistory) -> Edit {
+        match key.code {
+            KeyCode::Esc => return Edit::Cancel,
+            KeyCode::Enter
+                if key
+                    .modifiers
+                    .intersects(KeyModifiers::ALT | KeyModifiers::SHIFT) =>
+            {
+                self.edit().push('\n');
+            }
+            KeyCode::Enter => return Edit::Submit,
+            KeyCode::Backspace => {
+                self.edit().pop();
+            }
+            KeyCode::Up => self.recall_older(history),
+            KeyCode::Down => self.recall_newer(history),
+            KeyCode::Char(c) => self.edit().push(c),
+            _ => {}
+        }
+        Edit::Editing
+    }
+
+    /// Insert pasted text, with `\r\n` and `\r` line endings made `\n`.
+    pub fn paste(&mut self, text: &str) {
+        let text = text.replace("\r\n", "\n").replace('\r', "\n");
+        self.edit().push_str(&text);
+    }
+
+    /// The text, for changing; a recalled entry becomes a fresh draft.
+    fn edit(&mut self) -> &mut String {
+        self.recall = None;
+        &mut self.text
+    }
+
+    fn recall_older(&mut self, history: &PromptHistory) {
+        let next = self.recall.map_or(0, |i| i + 1);
+        if let Some(entry) = history.recent(next) {
+            if self.recall.is_none() {
+                self.draft = std::mem::take(&mut self.text);
+            }
+            self.text = entry.to_string();
+            self.recall = Some(next);
+        }
+    }
+
+    fn recall_newer(&mut self, history: &PromptHistory) {
+        match self.recall {
+            None => {}
+            Some(0) => {
+                self.text = std::mem::take(&mut self.draft);
+                self.recall = None;
+            }
+            Some(i) => {
+                self.text = history.recent(i - 1).unwrap_or_default().to_string();
+                self.recall = Some(i - 1);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn press(input: &mut PromptInput, history: &PromptHistory, code: KeyCode) -> Edit {
+        input.key(KeyEvent::new(code, KeyModifiers::NONE), history)
+    }
+
+    #[test]
+    fn alt_enter_and_paste_add_lines_without_submitting() {
+        let history = PromptHistory::default();
+        let mut input = PromptInput::default();
+        press(&mut input, &history, KeyCode::Char('a'));
+        let alt_enter = KeyEvent::new(KeyCode::Enter, KeyModifiers::ALT);
+        assert_eq!(input.key(alt_enter, &history), Edit::Editing);
+        input.paste("b\r\nc");
+        assert_eq!(input.text(), "a\nb\nc");
+        assert_eq!(press(&mut input, &history, KeyCode::Enter), Edit::Submit);
+    }
+
+    #[test]
+    fn up_and_down_recall_history_and_restore_draft() {
+        let mut history = PromptHistory::default();
+        history.push("first").unwrap();
+        history.push("second").unwrap();
+        let mut input = PromptInput::default();
+        press(&mut input, &history, KeyCode::Char('x'));
+
+        press(&mut input, &history, KeyCode::Up);
+        assert_eq!(input.text(), "second");
+        press(&mut input, &history, KeyCode::Up);
+        press(&mut input, &history, KeyCode::Up); // past the oldest: stays
+        assert_eq!(input.text(), "first");
+        press(&mut input, &history, KeyCode::Down);
+        assert_eq!(input.text(), "second");
+        press(&mut input, &history, KeyCode::Down);
+        assert_eq!(input.text(), "x");
+
+        // Editing a recalled entry makes it the draft.
+        press(&mut input, &history, KeyCode::Up);
+        press(&mut input, &history, KeyCode::Char('!'));
+        press(&mut input, &history, KeyCode::Down);
+        assert_eq!(input.text(), "second!");
+    }
+}
@@ -0,0 +1,672 @@
+//! Remappable key bindings for the agent list.
+//!
+//! Every normal-mode action has a stable snake_case name (`select_next`,
+//! `approve`, `kill_pane`, …) that the `[keys]` table in `ratatui.toml`
+//! binds to one or more key specs. A spec is a `+`-joined combo
+//! (`"Ctrl+d"`, `"Shift+Tab"`, `"K"`) and may be a space-separated chord
+//! (`"g g"`). User entries replace the default bindings for that action
+//! only; conflicts are rejected at load time rather than silently
+//! shadowing each other. Ctrl+c can't be bound: it quits from every mode.
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use serde::Deserialize;
+
+/// Normal-mode action a key chord can trigger.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Action {
+    SelectNext,
+    SelectPrev,
+    NextAttention,
+    FoldProjects,
+    Pin,
+    SortByDuration,
+    Input,
+    Broadcast,
+    History,
+    Export,
+    Diff,
+    Answer,
+    Settings,
+    Logs,
+    Worktrees,
+    Spawn,
+    Approve,
+    SendYes,
+    SendNo,
+    Reject,
+    KillPane,
+    Finish,
+    Refresh,
+    Help,
+    Quit,
+}
+
+impl Action {
+    /// All actions, in help-overlay display order.
+    pub const ALL: &'static [Action] = &[
+        Action::SelectNext,
+        Action::SelectPrev,
+        Action::NextAttention,
+        Action::FoldProjects,
+        Action::Pin,
+        Action::SortByDuration,
+        Action::Input,
+        Action::Broadcast,
+        Action::History,
+        Action::Export,
+        Action::Diff,
+        Action::Answer,
+        Action::Settings,
+        Action::Logs,
+        Action::Worktrees,
+        Action::Spawn,
+        Action::Approve,
+        Action::SendYes,
+        Action::SendNo,
+        Action::Reject,
+        Action::KillPane,
+        Action::Finish,
+        Action::Refresh,
+        Action::Help,
+        Action::Quit,
+    ];
+
+    /// The few actions the footer has room for; `?` lists the rest.
+    pub const FOOTER: &'static [Action] = &[
+        Action::SelectNext,
+        Action::NextAttention,
+        Action::Approve,
+        Action::Input,
+        Action::Spawn,
+        Action::Help,
+        Action::Quit,
+    ];
+
+    /// Config-file name of the action (`[keys]` table key).
+    pub fn name(self) -> &'static str {
+        match self {
+            Action::SelectNext => "select_next",
+            Action::SelectPrev => "select_prev",
+            Action::NextAttention => "next_attention",
+            Action::FoldProjects => "fold_projects",
+            Action::Pin => "pin",
+            Action::SortByDuration => "sort_by_duration",
+            Action::Input => "input",
+            Action::Broadcast => "broadcast",
+            Action::History => "history",
+            Action::Export => "export",
+            Action::Diff => "diff",
+            Action::Answer => "answer",
+            Action::Settings => "settings",
+            Action::Logs => "logs",
+            Action::Worktrees => "worktrees",
+            Action::Spawn => "spawn",
+            Action::Approve => "approve",
+            Action::SendYes => "send_yes",
+            Action::SendNo => "send_no",
+            Action::Reject => "reject",
+            Action::KillPane => "kill_pane",
+            Action::Finish => "finish",
+            Action::Refresh => "refresh",
+            Action::Help => "help",
+            Action::Quit => "quit",
+        }
+    }
+
+    /// Short footer label.
+    pub fn label(self) -> &'static str {
+        match self {
+            Action::SelectNext => "next",
+            Action::SelectPrev => "prev",
+            Action::NextAttention => "needs you",
+            Action::FoldProjects => "fold",
+            Action::Pin => "pin",
+            Action::SortByDuration => "sort",
+            Action::Input => "input",
+            Action::Broadcast => "broadcast",
+            Action::History => "history",
+            Action::Export => "export",
+            Action::Diff => "diff",
+            Action::Answer => "answer",
+            Action::Settings => "settings",
+            Action::Logs => "logs",
+            Action::Worktrees => "worktrees",
+            Action::Spawn => "spawn",
+            Action::Approve => "approve",
+            Action::SendYes => "yes",
+            Action::SendNo => "no",
+            Action::Reject => "reject",
+            Action::KillPane => "kill",
+            Action::Finish => "finish",
+            Action::Refresh => "refresh",
+            Action::Help => "help",
+            Action::Quit => "quit",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.iter().copied().find(|a| a.name() == name)
+    }
+
+    fn default_specs(self) -> &'static [&'static str] {
+        match self {
+            Action::SelectNext => &["j", "Down"],
+            Action::SelectPrev => &["k", "Up"],
+            Action::NextAttention => &["Tab"],
+            Action::FoldProjects => &["z"],
+            Action::Pin => &["p"],
+            Action::SortByDuration => &["o"],
+            Action::Input => &["i"],
+            Action::Broadcast => &["b"],
+            Action::History => &["v"],
+            Action::Export => &["e"],
+            Action::Diff => &["d"],
+            Action::Answer => &["A"],
+            Action::Settings => &["s"],
+            Action::Logs => &["L"],
+            Action::Worktrees => &["w"],
+            Action::Spawn => &["c"],
+            Action::Approve => &["a"],
+            Action::SendYes => &["y"],
+            Action::SendNo => &["n"],
+            Action::Reject => &["N"],
+            Action::KillPane => &["K"],
+            Action::Finish => &["F"],
+            Action::Refresh => &["r"],
+            Action::Help => &["?"],
+            Action::Quit => &["q", "Esc"],
+        }
+    }
+}
+
+/// One key press with its modifiers, normalized so that `Shift` is folded
+/// into the character for printable keys (`Shift+k` == `K`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct KeyCombo {
+    code: KeyCode,
+    modifiers: KeyModifiers,
+}
+
+impl KeyCombo {
+    fn new(code: KeyCode, modifiers: KeyModifiers) -> Self {
+        let mut modifiers =
+            modifiers & (KeyModifiers::CONTROL | KeyModifiers::ALT | KeyModifiers::SHIFT);
+        let code = match code {
+            KeyCode::Char(c) => {
+                modifiers.remove(KeyModifiers::SHIFT);
+                KeyCode::Char(c)
+            }
+            // Terminals report Shift+Tab as BackTab; keep one spelling.
+            KeyCode::BackTab => {
+                modifiers.remove(KeyModifiers::SHIFT);
+                KeyCode::BackTab
+            }
+            other => other,
+        };
+        Self { code, modifiers }
+    }
+
+    /// Ctrl+c, which quits from any mode before the keymap sees it.
+    fn is_quit(&self) -> bool {
+        self.modifiers.contains(KeyModifiers::CONTROL)
+            && matches!(self.code, KeyCode::Char('c' | 'C'))
+    }
+
+    fn parse(spec: &str) -> Result<Self, KeymapError> {
+        let invalid = || KeymapError::InvalidKey(spec.to_string());
+        let mut modifiers = KeyModifiers::NONE;
+        let mut parts: Vec<&str> = spec.split('+').collect();
+        // A trailing empty segment means the key itself is `+` ("Ctrl++").
+        let key = match parts.pop() {
+            Some("") if parts.last() == Some(&"") => {
+                parts.pop();
+                "+"
+            }
+            Some("") | None => return Err(invalid()),
+            Some(k) => k,
+        };
+        for m in parts {
+            match m.to_ascii_lowercase().as_str() {
+                "ctrl" | "control" => modifiers |= KeyModifiers::CONTROL,
+                "alt" | "meta" => modifiers |= KeyModifiers::ALT,
+                "shift" => modifiers |= KeyModifiers::SHIFT,
+                _ => return Err(invalid()),
+            }
+        }
+        let code = if key.chars().count() == 1 {
+            let c = key.chars().next().unwrap_or_default();
+            if modifiers.contains(KeyModifiers::SHIFT) {
+                KeyCode::Char(c.to_ascii_uppercase())
+            } else {
+                KeyCode::Char(c)
+            }
+        } else {
+            match key.to_ascii_lowercase().as_str() {
+                "enter" | "return" => KeyCode::Enter,
+                "esc" | "escape" => KeyCode::Esc,
+                "tab" if modifiers.contains(KeyModifiers::SHIFT) => KeyCode::BackTab,
+                "tab" => KeyCode::Tab,
+                "backtab" => KeyCode::BackTab,
+                "backspace" => KeyCode::Backspace,
+                "delete" | "del" => KeyCode::Delete,
+                "insert" | "ins" => KeyCode::Insert,
+                "space" => KeyCode::Char(' '),
+                "up" => KeyCode::Up,
+                "down" => KeyCode::Down,
+                "left" => KeyCode::Left,
+                "right" => KeyCode::Right,
+                "home" => KeyCode::Home,
+                "end" => KeyCode::End,
+                "pageup" | "pgup" => KeyCode::PageUp,
+                "pagedown" | "pgdn" => KeyCode::PageDown,
+                f if f.starts_with('f') => f[1..]
+                    .parse::<u8>()
+                    .ok()
+                    .filter(|n| (1..=24).contains(n))
+                    .map(KeyCode::F)
+                    .ok_or_else(invalid)?,
+                _ => return Err(invalid()),
+            }
+        };
+        Ok(Self::new(code, modifiers))
+    }
+}
+
+impl From<KeyEvent> for KeyCombo {
+    fn from(key: KeyEvent) -> Self {
+        Self::new(key.code, key.modifiers)
+    }
+}
+
+impl fmt::Display for KeyCombo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            f.write_str("Ctrl+")?;
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            f.write_str("Alt+")?;
+        }
+        if self.modifiers.contains(KeyModifiers::SHIFT) {
+            f.write_str("Shift+")?;
+        }
+        match self.code {
+            KeyCode::Char(' ') => f.write_str("Space"),
+            KeyCode::Char(c) => write!(f, "{c}"),
+            KeyCode::Enter => f.write_str("Enter"),
+            KeyCode::Esc => f.write_str("Esc"),
+            KeyCode::Tab => f.write_str("Tab"),
+            KeyCode::BackTab => f.write_str("Shift+Tab"),
+            KeyCode::Backspace => f.write_str("Backspace"),
+            KeyCode::Delete => f.write_str("Del"),
+            KeyCode::Insert => f.write_str("Ins"),
+            KeyCode::Up => f.write_str("↑"),
+            KeyCode::Down => f.write_str("↓"),
+            KeyCode::Left => f.write_str("←"),
+            KeyCode::Right => f.write_str("→"),
+            KeyCode::Home => f.write_str("Home"),
+            KeyCode::End => f.write_str("End"),
+            KeyCode::PageUp => f.write_str("PgUp"),
+            KeyCode::PageDown => f.write_str("PgDn"),
+            KeyCode::F(n) => write!(f, "F{n}"),
+            _ => f.write_str("?"),
+        }
+    }
+}
+
+/// A sequence of key presses that triggers one action (`"g g"`).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct KeyChord(Vec<KeyCombo>);
+
+impl KeyChord {
+    pub fn parse(spec: &str) -> Result<Self, KeymapError> {
+        let combos = spec
+            .split_whitespace()
+            .map(KeyCombo::parse)
+            .collect::<Result<Vec<_>, _>>()?;
+        if combos.is_empty() {
+            return Err(KeymapError::InvalidKey(spec.to_string()));
+        }
+        Ok(Self(combos))
+    }
+
+    fn starts_with(&self, prefix: &[KeyCombo]) -> bool {
+        self.0.starts_with(prefix)
+    }
+}
+
+impl fmt::Display for KeyChord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, combo) in self.0.iter().enumerate() {
+            if i > 0 {
+                f.write_str(" ")?;
+            }
+            write!(f, "{combo}")?;
+        }
+        Ok(())
+    }
+}
+
+/// One or more key specs as written in `[keys]`: either a bare string or
+/// an array of strings.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum KeySpec {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl KeySpec {
+    fn specs(&self) -> Vec<&str> {
+        match self {
+            KeySpec::One(s) => vec![s.as_str()],
+            KeySpec::Many(v) => v.iter().map(String::as_str).collect(),
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+pub enum KeymapError {
+    #[error("unknown action `{0}` in [keys]")]
+    UnknownAction(String),
+    #[error("invalid key spec `{0}`")]
+    InvalidKey(String),
+    #[error("`{chord}` is bound to both `{first}` and `{second}`")]
+    Conflict {
+        chord: String,
+        first: &'static str,
+        second: &'static str,
+    },
+    #[error("`{chord}` ({action}) uses Ctrl+c, which always quits")]
+    Reserved { chord: String, action: &'static str },
+    #[error("`{prefix}` ({prefix_action}) is a prefix of `{chord}` ({action}); the longer chord could never fire")]
+    PrefixConflict {
+        prefix: String,
+        prefix_action: &'static str,
+        chord: String,
+        action: &'static str,
+    },
+}
+
+/// Outcome of feeding the pending key sequence to the keymap.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Resolution {
+    Matched(Action),
+    /// The sequence is a strict prefix of at least one chord — wait for
+    /// the next key.
+    Pending,
+    NoMatch,
+}
+
+#[derive(Debug, Clone)]
+pub struct Keymap {
+    bindings: Vec<(KeyChord, Action)>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Self::from_overrides(&BTreeMap::new()).expect("default keymap is conflict-free")
+    }
+}
+
+impl Keymap {
+    /// Build the keymap from the `[keys]` table. Actions absent from
+    /// `overrides` keep their default bindings.
+    pub fn from_overrides(overrides: &BTreeMap<String, KeySpec>) -> Result<Self, KeymapError> {
+        let mut resolved: BTreeMap<Action, Vec<&str>> = Action::ALL
+            .iter()
+            .map(|&a| (a, a.default_specs().to_vec()))
+            .collect();
+        for (name, spec) in overrides {
+            let action =
+                Action::from_name(name).ok_or_else(|| KeymapError::UnknownAction(name.clone()))?;
+            resolved.insert(action, spec.specs());
+        }
+
+        let mut bindings = Vec::new();
+        for action in Action::ALL {
+            for spec in &resolved[action] {
+                let chord = KeyChord::parse(spec)?;
+                if chord.0.iter().any(KeyCombo::is_quit) {
+                    return Err(KeymapError::Reserved {
+                        chord: chord.to_string(),
+                        action: action.name(),
+                    });
+                }
+                bindings.push((chord, *action));
+            }
+        }
+
+        let map = Self { bindings };
+        map.validate()?;
+        Ok(map)
+    }
+
+    fn validate(&self) -> Result<(), KeymapError> {
+        for (i, (a_chord, a_action)) in self.bindings.iter().enumerate() {
+            for (b_chord, b_action) in &self.bindings[i + 1..] {
+                if a_chord == b_chord {
+                    if a_action == b_action {
+                        continue;
+                    }
+                    return Err(KeymapError::Conflict {
+                        chord: a_chord.to_string(),
+                        first: a_action.name(),
+                        second: b_action.name(),
+                    });
+                }
+                let (short, short_action, long, long_action) = if a_chord.0.len() < b_chord.0.len()
+                {
+                    (a_chord, a_action, b_chord, b_action)
+                } else {
+                    (b_chord, b_action, a_chord, a_action)
+                };
+                if long.starts_with(&short.0) {
+                    return Err(KeymapError::PrefixConflict {
+                        prefix: short.to_string(),
+                        prefix_action: short_action.name(),
+                        chord: long.to_string(),
+                        action: long_action.name(),
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Resolve the keys pressed so far (oldest first).
+    pub fn resolve(&self, pending: &[KeyCombo]) -> Resolution {
+        let mut prefix = false;
+        for (chord, action) in &self.bindings {
+            if chord.0 == pending {
+                return Resolution::Matched(*action);
+            }
+            if chord.starts_with(pending) {
+                prefix = true;
+            }
+        }
+        if prefix {
+            Resolution::Pending
+        } else {
+            Resolution::NoMatch
+        }
+    }
+
+    /// Footer label listing every chord bound to `action` in binding
+    /// order, joined with `/`, e.g. `"j/↓"`. Empty when it is unbound.
+    pub fn hint(&self, action: Action) -> String {
+        self.bindings
+            .iter()
+            .filter(|(_, a)| *a == action)
+            .map(|(c, _)| c.to_string())
+            .collect::<Vec<_>>()
+            .join("/")
+    }
+}
+
+/// Accumulates key presses until they resolve to an action or fall off
+/// every chord.
+#[derive(Debug, Default)]
+pub struct ChordBuffer {
+    pending: Vec<KeyCombo>,
+}
+
+impl ChordBuffer {
+    /// Feed one key press. Returns the action once a chord completes.
+    pub fn feed(&mut self, keymap: &Keymap, key: KeyCombo) -> Option<Action> {
+        let restarted = !self.pending.is_empty();
+        self.pending.push(key);
+        match keymap.resolve(&self.pending) {
+            Resolution::Matched(action) => {
+                self.pending.clear();
+                Some(action)
+            }
+            Resolution::Pending => None,
+            Resolution::NoMatch => {
+                self.pending.clear();
+                // A stray key after a chord prefix starts matching afresh
+                // from that key alone, so `g x` with `x` bound still fires.
+                if restarted {
+                    self.feed(keymap, key)
+                } else {
+                    None
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn combo(code: KeyCode, modifiers: KeyModifiers) -> KeyCombo {
+        KeyCombo::from(KeyEvent::new(code, modifiers))
+    }
+
+    fn overrides(pairs: &[(&str, &[&str])]) -> BTreeMap<String, KeySpec> {
+        pairs
+            .iter()
+            .map(|(k, v)| {
+                (
+                    k.to_string(),
+                    KeySpec::Many(v.iter().map(|s| s.to_string()).collect()),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn defaults_resolve_single_keys() {
+        let map = Keymap::default();
+        assert_eq!(
+            map.resolve(&[combo(KeyCode::Char('j'), KeyModifiers::NONE)]),
+            Resolution::Matched(Action::SelectNext)
+        );
+        assert_eq!(
+            map.resolve(&[combo(KeyCode::Down, KeyModifiers::NONE)]),
+            Resolution::Matched(Action::SelectNext)
+        );
+        assert_eq!(
+            map.resolve(&[combo(KeyCode::Char('x'), KeyModifiers::NONE)]),
+            Resolution::NoMatch
+        );
+    }
+
+    #[test]
+    fn shifted_letters_match_uppercase_spec() {
+        let map = Keymap::default();
+        // Terminals report `K` as Char('K') + SHIFT.
+        assert_eq!(
+            map.resolve(&[combo(KeyCode::Char('K'), KeyModifiers::SHIFT)]),
+            Resolution::Matched(Action::KillPane)
+        );
+        assert_eq!(KeyCombo::parse("Shift+k"), KeyCombo::parse("K"));
+    }
+
+    #[test]
+    fn ctrl_combo_parses() {
+        let c = KeyCombo::parse("Ctrl+d").unwrap();
+        assert_eq!(c, combo(KeyCode::Char('d'), KeyModifiers::CONTROL));
+        assert_eq!(c.to_string(), "Ctrl+d");
+    }
+
+    #[test]
+    fn override_replaces_only_that_action() {
+        let map = Keymap::from_overrides(&overrides(&[("approve", &["Enter"])])).unwrap();
+        assert_eq!(
+            map.resolve(&[combo(KeyCode::Enter, KeyModifiers::NONE)]),
+            Resolution::Matched(Action::Approve)
+        );
+        assert_eq!(
+            map.resolve(&[combo(KeyCode::Char('a'), KeyModifiers::NONE)]),
+            Resolution::NoMatch
+        );
+        assert_eq!(map.hint(Action::SelectNext), "j/↓");
+    }
+
+    #[test]
+    fn chords_report_pending_then_match() {
+        let map = Keymap::from_overrides(&overrides(&[("refresh", &["g r"])])).unwrap();
+        let g = combo(KeyCode::Char('g'), KeyModifiers::NONE);
+        let r = combo(KeyCode::Char('r'), KeyModifiers::NONE);
+        assert_eq!(map.resolve(&[g]), Resolution::Pending);
+        assert_eq!(map.resolve(&[g, r]), Resolution::Matched(Action::Refresh));
+        assert_eq!(map.resolve(&[r]), Resolution::NoMatch);
+    }
+
+    #[test]
+    fn chord_buffer_restarts_after_stray_key() {
+        let map = Keymap::from_overrides(&overrides(&[("refresh", &["g r"])])).unwrap();
+        let g = combo(KeyCode::Char('g'), KeyModifiers::NONE);
+        let r = combo(KeyCode::Char('r'), KeyModifiers::NONE);
+        let j = combo(KeyCode::Char('j'), KeyModifiers::NONE);
+        let mut buf = ChordBuffer::default();
+        assert_eq!(buf.feed(&map, g), None);
+        assert_eq!(buf.feed(&map, r), Some(Action::Refresh));
+        assert_eq!(buf.feed(&map, g), None);
+        assert_eq!(buf.feed(&map, j), Some(Action::SelectNext));
+    }
+
+    #[test]
+    fn duplicate_binding_is_a_conflict() {
+        let err = Keymap::from_overrides(&overrides(&[("approve", &["j"])])).unwrap_err();
+        assert_eq!(
+            err,
+            KeymapError::Conflict {
+                chord: "j".into(),
+                first: "select_next",
+                second: "approve",
+            }
+        );
+    }
+
+    #[test]
+    fn prefix_binding_is_a_conflict() {
+        let err = Keymap::from_overrides(&overrides(&[("refresh", &["a a"])])).unwrap_err();
+        assert!(matches!(err, KeymapError::PrefixConflict { .. }), "{err}");
+    }
+
+    #[test]
+    fn unknown_action_and_bad_key_are_rejected() {
+        assert_eq!(
+            Keymap::from_overrides(&overrides(&[("explode", &["x"])])).unwrap_err(),
+            KeymapError::UnknownAction("explode".into())
+        );
+        assert_eq!(
+            Keymap::from_overrides(&overrides(&[("quit", &["Hyper+x"])])).unwrap_err(),
+            KeymapError::InvalidKey("Hyper+x".into())
+        );
+    }
+
+    #[test]
+    fn ctrl_c_cannot_be_bound() {
+        for spec in ["Ctrl+c", "g Ctrl+c", "Ctrl+C"] {
+            let err = Keymap::from_overrides(&overrides(&[("refresh", &[spec])])).unwrap_err();
+            assert!(matches!(err, KeymapError::Reserved { .. }), "{spec}: {err}");
+        }
+        Keymap::from_overrides(&overrides(&[("refresh", &["Ctrl+r"])])).unwrap();
+    }
+}
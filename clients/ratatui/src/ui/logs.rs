@@ -0,0 +1,197 @@
+//! Log overlay: this client's own log from the in-memory ring buffer.
+//!
+//! Shows the newest lines at the bottom and follows new ones until
+//! scrolled up. `f` cycles the minimum level shown, `/` filters by a
+//! case-insensitive substring of the target or message, and `v` raises
+//! what is captured in the first place (see [`crate::logbuf::LevelControl`]).
+
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph},
+    Frame,
+};
+use tracing::Level;
+
+use crate::logbuf::LogLine;
+
+#[derive(Debug, Clone)]
+pub struct LogsView {
+    /// Most verbose level shown.
+    pub min_level: Level,
+    pub query: String,
+    /// Typing into `query`.
+    pub editing: bool,
+    /// Lines scrolled up from the bottom; 0 follows new lines.
+    pub scroll: usize,
+}
+
+impl Default for LogsView {
+    fn default() -> Self {
+        Self {
+            min_level: Level::TRACE,
+            query: String::new(),
+            editing: false,
+            scroll: 0,
+        }
+    }
+}
+
+impl LogsView {
+    /// ERROR → WARN → INFO → DEBUG → TRACE → ERROR.
+    pub fn cycle_level(&mut self) {
+        self.min_level = match self.min_level {
+            Level::ERROR => Level::WARN,
+            Level::WARN => Level::INFO,
+            Level::INFO => Level::DEBUG,
+            Level::DEBUG => Level::TRACE,
+            _ => Level::ERROR,
+        };
+        self.scroll = 0;
+    }
+
+    /// Lines passing the level and search filters, oldest first.
+    pub fn visible<'a>(&self, lines: &'a [LogLine]) -> Vec<&'a LogLine> {
+        let query = self.query.to_lowercase();
+        lines
+            .iter()
+            .filter(|l| l.level <= self.min_level)
+            .filter(|l| {
+                query.is_empty()
+                    || l.message.to_lowercase().contains(&query)
+                    || l.target.to_lowercase().contains(&query)
+            })
+            .collect()
+    }
+}
+
+fn level_style(level: Level) -> Style {
+    match level {
+        Level::ERROR => Style::default().fg(Color::Red),
+        Level::WARN => Style::default().fg(Color::Yellow),
+        Level::INFO => Style::default().fg(Color::Green),
+        _ => Style::default().fg(Color::DarkGray),
+    }
+}
+
+/// `capture` is the active filter directive string, shown in the title.
+pub fn render(
+    frame: &mut Frame,
+    area: Rect,
+    view: &LogsView,
+    lines: &[LogLine],
+    capture: &str,
+    status: &str,
+) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Min(1),    // log
+            Constraint::Length(3), // search / hint box
+            Constraint::Length(1), // status
+        ])
+        .split(area);
+
+    let visible = view.visible(lines);
+    let height = chunks[0].height.saturating_sub(2) as usize;
+    // Scrolled to the top still fills the window.
+    let end = visible
+        .len()
+        .saturating_sub(view.scroll)
+        .max(height.min(visible.len()));
+    let start = end.saturating_sub(height);
+    let body: Vec<Line> = visible[start..end]
+        .iter()
+        .map(|l| {
+            Line::from(vec![
+                Span::styled(
+                    l.at.format("%H:%M:%S ").to_string(),
+                    Style::default().fg(Color::DarkGray),
+                ),
+                Span::styled(format!("{:<5} ", l.level), level_style(l.level)),
+                Span::styled(
+                    format!("{}: ", l.target),
+                    Style::default().fg(Color::DarkGray),
+                ),
+                Span::raw(l.message.clone()),
+            ])
+        })
+        .collect();
+    let follow = if view.scroll == 0 { ", following" } else { "" };
+    let title = format!(
+        " log ≤{} ({}/{} lines{follow}; capturing {capture}) ",
+        view.min_level,
+        visible.len(),
+        lines.len()
+    );
+    frame.render_widget(
+        Paragraph::new(body).block(Block::default().borders(Borders::ALL).title(title)),
+        chunks[0],
+    );
+
+    let hint = if view.editing {
+        Paragraph::new(view.query.clone())
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(" search (Enter to apply, Esc to clear) "),
+            )
+            .style(Style::default().fg(Color::Yellow))
+    } else {
+        let search = if view.query.is_empty() {
+            String::new()
+        } else {
+            format!("   filter: {}", view.query)
+        };
+        Paragraph::new(format!(
+            "j/k scroll  g/G top/bottom  f level  / search  v capture more  Esc close{search}"
+        ))
+        .block(Block::default().borders(Borders::ALL).title(" keys "))
+    };
+    frame.render_widget(hint, chunks[1]);
+
+    frame.render_widget(
+        Paragraph::new(status.to_string()).style(Style::default().fg(Color::DarkGray)),
+        chunks[2],
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Local;
+
+    fn line(level: Level, target: &str, message: &str) -> LogLine {
+        LogLine {
+            at: Local::now(),
+            level,
+            target: target.into(),
+            message: message.into(),
+        }
+    }
+
+    #[test]
+    fn filters_by_level_and_query() {
+        let lines = vec![
+            line(Level::DEBUG, "reqwest", "connecting"),
+            line(
+                Level::WARN,
+                "tmai_ratatui::hooks",
+                "hook halted for a: exit 1",
+            ),
+            line(Level::INFO, "tmai_ratatui::ui::app", "finished b"),
+        ];
+        let mut v = LogsView::default();
+        assert_eq!(v.visible(&lines).len(), 3);
+        v.cycle_level(); // ERROR
+        assert!(v.visible(&lines).is_empty());
+        v.cycle_level(); // WARN
+        v.cycle_level(); // INFO
+        assert_eq!(v.visible(&lines).len(), 2);
+        v.query = "HOOKS".into();
+        let hits = v.visible(&lines);
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].level, Level::WARN);
+    }
+}
@@ -1,4 +1,22 @@
 pub mod app;
+pub mod approvals;
+pub mod attention;
+pub mod choices;
+pub mod diff;
+pub mod durations;
+pub mod finish;
+pub mod git_info;
+pub mod groups;
+pub mod help;
+pub mod history;
+pub mod input;
+pub mod keymap;
+pub mod logs;
+pub mod presets;
 pub mod session_list;
+pub mod settings;
+pub mod stats;
+pub mod templates;
+pub mod worktrees;
 
 pub use app::{run, InputMode};
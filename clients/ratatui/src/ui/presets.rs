@@ -0,0 +1,421 @@
+//! Agent presets for spawning new agents.
+//!
+//! A preset names a command line (`claude --model opus …`) so launching a
+//! particular kind of agent is two key presses. Presets come from
+//! `[[presets]]` in `ratatui.toml`; with none configured a bare `claude`
+//! is offered. Spawning goes through `POST /api/spawn`, which takes no
+//! environment, so per-preset variables are set with `env` as the command.
+//!
+//! A preset may carry an `initial_prompt` template. `{repo}`, `{branch}`
+//! and `{cwd}` are filled from the launch directory; a template using
+//! `{task}` asks for the task text before spawning.
+//!
+//! The spawn response only carries the PTY `session_id`, and nothing in
+//! the API promises that the prompt endpoint accepts it. The new agent's
+//! snapshot does carry it, as `target` (the React client resolves spawned
+//! sessions the same way), so the prompt waits in [`PendingPrompts`] until
+//! that agent shows up and is then sent to its real id. One that hasn't
+//! shown up after [`PROMPT_WAIT`] is dropped.
+
+use std::time::{Duration, Instant};
+
+use anyhow::{bail, Result};
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph},
+    Frame,
+};
+use serde::Deserialize;
+
+use crate::types::AgentSnapshot;
+
+/// How long an initial prompt waits for its agent to appear.
+pub const PROMPT_WAIT: Duration = Duration::from_secs(120);
+
+/// One `[[presets]]` entry.
+#[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct Preset {
+    pub name: String,
+    pub command: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+    /// Working directory; defaults to the selected agent's.
+    #[serde(default)]
+    pub cwd: Option<String>,
+    /// First prompt template sent once the agent is up.
+    #[serde(default)]
+    pub initial_prompt: Option<String>,
+}
+
+impl Preset {
+    /// `command arg1 arg2` for display.
+    pub fn command_line(&self) -> String {
+        std::iter::once(self.command.as_str())
+            .chain(self.args.iter().map(String::as_str))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// The initial prompt wants a task typed in before spawning.
+    pub fn needs_task(&self) -> bool {
+        self.initial_prompt
+            .as_deref()
+            .is_some_and(|t| t.contains("{task}"))
+    }
+}
+
+/// Replace `{name}` placeholders in one pass; unknown ones are left as
+/// they are. Inserted values are never expanded again, so a task that
+/// mentions `{branch}` stays as typed.
+pub fn fill(template: &str, vars: &[(&str, &str)]) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(open) = rest.find('{') {
+        out.push_str(&rest[..open]);
+        let after = &rest[open + 1..];
+        let known = after.find('}').and_then(|close| {
+            let name = &after[..close];
+            vars.iter()
+                .find(|(n, _)| *n == name)
+                .map(|(_, value)| (*value, close))
+        });
+        match known {
+            Some((value, close)) => {
+                out.push_str(value);
+                rest = &after[close + 1..];
+            }
+            None => {
+                out.push('{');
+                rest = after;
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// An initial prompt waiting for its spawned agent.
+#[derive(Debug, Clone)]
+pub struct PendingPrompt {
+    /// `session_id` from the spawn response.
+    pub session_id: String,
+    pub preset: String,
+    pub prompt: String,
+    since: Instant,
+}
+
+#[derive(Debug, Default)]
+pub struct PendingPrompts {
+    pending: Vec<PendingPrompt>,
+}
+
+impl PendingPrompts {
+    pub fn push(&mut self, session_id: String, preset: String, prompt: String, now: Instant) {
+        self.pending.push(PendingPrompt {
+            session_id,
+            preset,
+            prompt,
+            since: now,
+        });
+    }
+
+    /// Take the prompts whose agent is in `agents`, each with that
+    /// agent's id.
+    pub fn take_ready(&mut self, agents: &[AgentSnapshot]) -> Vec<(String, PendingPrompt)> {
+        let mut ready = Vec::new();
+        self.pending.retain(|p| {
+            let agent = agents
+                .iter()
+                .find(|a| a.target == p.session_id || a.id == p.session_id);
+            match agent {
+                Some(agent) => {
+                    ready.push((agent.id.clone(), p.clone()));
+                    false
+                }
+                None => true,
+            }
+        });
+        ready
+    }
+
+    /// Take the prompts that have waited longer than [`PROMPT_WAIT`].
+    pub fn take_expired(&mut self, now: Instant) -> Vec<PendingPrompt> {
+        let (expired, waiting) = std::mem::take(&mut self.pending)
+            .into_iter()
+            .partition(|p| now.duration_since(p.since) > PROMPT_WAIT);
+        self.pending = waiting;
+        expired
+    }
+}
+
+/// Presets offered when the config defines none.
+pub fn builtin() -> Vec<Preset> {
+    vec![Preset {
+        name: "claude".into(),
+        command: "claude".into(),
+        args: Vec::new(),
+        cwd: None,
+        initial_prompt: None,
+    }]
+}
+
+/// Reject empty commands and duplicate names.
+pub fn validate(presets: &[Preset]) -> Result<()> {
+    for (i, p) in presets.iter().enumerate() {
+        if p.command.trim().is_empty() {
+            bail!("preset {:?} has an empty command", p.name);
+        }
+        if presets[..i].iter().any(|q| q.name == p.name) {
+            bail!("preset {:?} is defined twice", p.name);
+        }
+    }
+    Ok(())
+}
+
+#[derive(Debug, Clone)]
+pub struct PresetsView {
+    pub presets: Vec<Preset>,
+    pub selected: usize,
+    /// Directory used for presets without their own `cwd`.
+    pub cwd: String,
+    /// Branch checked out in `cwd`, when known.
+    pub branch: Option<String>,
+    /// Task text being typed for a `{task}` prompt.
+    pub task: Option<String>,
+}
+
+impl PresetsView {
+    pub fn new(presets: &[Preset], cwd: String, branch: Option<String>) -> Self {
+        let presets = if presets.is_empty() {
+            builtin()
+        } else {
+            presets.to_vec()
+        };
+        Self {
+            presets,
+            selected: 0,
+            cwd,
+            branch,
+            task: None,
+        }
+    }
+
+    pub fn current(&self) -> Option<&Preset> {
+        self.presets.get(self.selected)
+    }
+
+    pub fn select_next(&mut self) {
+        if !self.presets.is_empty() {
+            self.selected = (self.selected + 1) % self.presets.len();
+        }
+    }
+
+    pub fn select_prev(&mut self) {
+        if !self.presets.is_empty() {
+            self.selected = (self.selected + self.presets.len() - 1) % self.presets.len();
+        }
+    }
+
+    /// Jump straight to preset `n` (1-based), as typed on the number row.
+    pub fn select_number(&mut self, n: usize) -> bool {
+        if (1..=self.presets.len()).contains(&n) {
+            self.selected = n - 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Directory the preset will be launched in.
+    pub fn cwd_for<'a>(&'a self, preset: &'a Preset) -> &'a str {
+        preset.cwd.as_deref().unwrap_or(&self.cwd)
+    }
+
+    /// `preset`'s initial prompt with its variables filled in.
+    pub fn initial_prompt(&self, preset: &Preset, task: &str) -> Option<String> {
+        let template = preset.initial_prompt.as_deref()?;
+        let cwd = self.cwd_for(preset);
+        let repo = cwd.trim_end_matches('/').rsplit('/').next().unwrap_or("");
+        // The selected agent's branch says nothing about a preset's own dir.
+        let branch = match preset.cwd {
+            None => self.branch.as_deref().unwrap_or(""),
+            Some(_) => "",
+        };
+        Some(fill(
+            template,
+            &[
+                ("repo", repo),
+                ("branch", branch),
+                ("cwd", cwd),
+                ("task", task),
+            ],
+        ))
+    }
+}
+
+pub fn render(frame: &mut Frame, area: Rect, view: &PresetsView, status_line: &str) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Min(1),    // presets
+            Constraint::Length(3), // hint box
+            Constraint::Length(1), // status
+        ])
+        .split(area);
+
+    let items: Vec<ListItem> = view
+        .presets
+        .iter()
+        .enumerate()
+        .map(|(i, p)| {
+            let mut spans = vec![
+                Span::styled(format!("{}. ", i + 1), Style::default().fg(Color::DarkGray)),
+                Span::styled(p.name.clone(), Style::default().fg(Color::Cyan)),
+                Span::raw(format!("  {}", p.command_line())),
+            ];
+            if let Some(cwd) = &p.cwd {
+                spans.push(Span::styled(
+                    format!("  in {cwd}"),
+                    Style::default().fg(Color::DarkGray),
+                ));
+            }
+            ListItem::new(Line::from(spans))
+        })
+        .collect();
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(" spawn agent "),
+        )
+        .highlight_style(
+            Style::default()
+                .bg(Color::DarkGray)
+                .add_modifier(Modifier::BOLD),
+        )
+        .highlight_symbol("▶ ");
+    let mut state = ListState::default();
+    if !view.presets.is_empty() {
+        state.select(Some(view.selected.min(view.presets.len() - 1)));
+    }
+    frame.render_stateful_widget(list, chunks[0], &mut state);
+
+    let hint = match &view.task {
+        Some(buf) => Paragraph::new(buf.clone())
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(" task (Enter to spawn, Esc to cancel) "),
+            )
+            .style(Style::default().fg(Color::Yellow)),
+        None => Paragraph::new(format!(
+            "j/k or 1-9 pick  Enter spawn  Esc cancel   default dir: {}",
+            view.cwd
+        ))
+        .block(Block::default().borders(Borders::ALL).title(" keys ")),
+    };
+    frame.render_widget(hint, chunks[1]);
+
+    let status =
+        Paragraph::new(status_line.to_string()).style(Style::default().fg(Color::DarkGray));
+    frame.render_widget(status, chunks[2]);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::test_support;
+
+    fn preset(name: &str, command: &str) -> Preset {
+        Preset {
+            name: name.into(),
+            command: command.into(),
+            args: Vec::new(),
+            cwd: None,
+            initial_prompt: None,
+        }
+    }
+
+    #[test]
+    fn validate_rejects_duplicates_and_empty_commands() {
+        validate(&[preset("a", "claude"), preset("b", "codex")]).unwrap();
+        assert!(validate(&[preset("a", "claude"), preset("a", "codex")]).is_err());
+        assert!(validate(&[preset("a", " ")]).is_err());
+    }
+
+    #[test]
+    fn view_falls_back_to_builtin_and_picks_cwd() {
+        let v = PresetsView::new(&[], "/src/app".into(), None);
+        assert_eq!(v.presets, builtin());
+        let mut p = preset("reviewer", "claude");
+        p.args = vec!["--model".into(), "opus".into()];
+        assert_eq!(p.command_line(), "claude --model opus");
+        assert_eq!(v.cwd_for(&p), "/src/app");
+        p.cwd = Some("/src/other".into());
+        assert_eq!(v.cwd_for(&p), "/src/other");
+
+        let mut v = PresetsView::new(&[preset("a", "x"), p], "/".into(), None);
+        assert!(v.select_number(2));
+        assert_eq!(v.current().unwrap().name, "reviewer");
+        assert!(!v.select_number(3));
+    }
+
+    #[test]
+    fn fill_does_not_expand_inserted_values() {
+        let vars = [("task", "mention {branch} and {task}"), ("branch", "main")];
+        assert_eq!(
+            fill("{branch}: {task} {other} {", &vars),
+            "main: mention {branch} and {task} {other} {"
+        );
+    }
+
+    #[test]
+    fn pending_prompt_goes_to_the_agent_that_appears() {
+        let agent = |id: &str, target: &str| {
+            let mut a = test_support::agent(id, None);
+            a.target = target.into();
+            a
+        };
+        let start = Instant::now();
+        let mut pending = PendingPrompts::default();
+        pending.push("pty-1".into(), "worker".into(), "go".into(), start);
+        pending.push("pty-2".into(), "reviewer".into(), "look".into(), start);
+
+        assert!(pending
+            .take_ready(&[agent("claude:a", "main:0.0")])
+            .is_empty());
+        let ready = pending.take_ready(&[agent("claude:b", "pty-1")]);
+        assert_eq!(ready.len(), 1);
+        assert_eq!(
+            (ready[0].0.as_str(), ready[0].1.prompt.as_str()),
+            ("claude:b", "go")
+        );
+        assert!(pending.take_ready(&[agent("claude:b", "pty-1")]).is_empty());
+
+        assert!(pending.take_expired(start + PROMPT_WAIT).is_empty());
+        let expired = pending.take_expired(start + PROMPT_WAIT + Duration::from_secs(1));
+        assert_eq!(expired.len(), 1);
+        assert_eq!(expired[0].preset, "reviewer");
+    }
+
+    #[test]
+    fn initial_prompt_fills_variables() {
+        let v = PresetsView::new(&[], "/src/app/".into(), Some("feat-x".into()));
+        let mut p = preset("worker", "claude");
+        assert!(v.initial_prompt(&p, "").is_none());
+        p.initial_prompt = Some("In {repo} on {branch}: {task} {unknown}".into());
+        assert!(p.needs_task());
+        assert_eq!(
+            v.initial_prompt(&p, "fix the build").as_deref(),
+            Some("In app on feat-x: fix the build {unknown}")
+        );
+        p.cwd = Some("/src/other".into());
+        assert_eq!(
+            v.initial_prompt(&p, "x").as_deref(),
+            Some("In other on : x {unknown}")
+        );
+    }
+}
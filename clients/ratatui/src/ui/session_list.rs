@@ -1,79 +1,164 @@
-//! Single-screen agent list renderer.
+//! Main screen: the agent list and everything around it.
 //!
-//! The legacy bundled TUI's `session_list.rs` was 1397 lines with a full
-//! multi-pane layout, team overview, status bar, usage bar, previews,
-//! confirmation popups and more. This milestone ports only the bare
-//! minimum: a scrollable agent list with phase/status indicators and a
-//! footer showing current key bindings.
+//! Top to bottom: a header with vendor availability, the agent list
+//! (grouped by project, pinned first, with attention tags and state
+//! durations), a git summary of the selected agent, the input box and the
+//! status line. In normal mode the input box shows the most common key
+//! bindings; `?` opens the full list. Other views (history, diff,
+//! settings, …) replace this screen while open.
+
+use std::time::Instant;
 
 use ratatui::{
     layout::{Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
-    text::{Line, Span},
+    text::{Line, Span, Text},
     widgets::{Block, Borders, List, ListItem, ListState, Paragraph},
     Frame,
 };
 
-use crate::types::{attention_label, AgentAttention, AgentSnapshot};
+use crate::pins::Pins;
+use crate::types::{
+    attention_label, AgentAttention, AgentSnapshot, VendorAvailability, VendorStatus,
+};
+use crate::ui::durations::StateClock;
+use crate::ui::groups::Group;
+use crate::ui::keymap::{Action, Keymap};
 
 pub struct SessionListView<'a> {
     pub agents: &'a [AgentSnapshot],
+    /// Project groups over `agents`; headers are drawn when there are two
+    /// or more.
+    pub groups: &'a [Group],
+    /// When set, only this group's members are listed; the others show
+    /// just their header and roll-up.
+    pub unfolded: Option<usize>,
+    pub pins: &'a Pins,
+    pub clock: &'a StateClock,
+    pub vendors: &'a [VendorStatus],
     pub selected: usize,
+    /// Git summary of the selected agent, shown under the list.
+    pub detail: Option<&'a str>,
     pub input_mode: InputModeView<'a>,
     pub status_line: &'a str,
+    pub keymap: &'a Keymap,
 }
 
 #[derive(Debug, Clone, Copy)]
 pub enum InputModeView<'a> {
     Normal,
-    Text { buffer: &'a str },
+    Text { title: &'a str, buffer: &'a str },
     Confirm { prompt: &'a str },
 }
 
+/// Most lines of a multi-line prompt shown at once; the input box keeps
+/// the end in view.
+const MAX_INPUT_LINES: usize = 8;
+
 pub fn render(frame: &mut Frame, area: Rect, view: SessionListView<'_>) {
+    let input_lines = match view.input_mode {
+        InputModeView::Text { buffer, .. } => buffer.split('\n').count().min(MAX_INPUT_LINES),
+        _ => 1,
+    };
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
-            Constraint::Length(1), // header
-            Constraint::Min(1),    // list
-            Constraint::Length(3), // input / hint box
-            Constraint::Length(1), // status
+            Constraint::Length(1),                      // header
+            Constraint::Min(1),                         // list
+            Constraint::Length(1),                      // selected-agent detail
+            Constraint::Length(input_lines as u16 + 2), // input / hint box
+            Constraint::Length(1),                      // status
         ])
         .split(area);
 
-    render_header(frame, chunks[0], view.agents.len());
-    render_list(frame, chunks[1], view.agents, view.selected);
-    render_input(frame, chunks[2], view.input_mode);
-    render_status(frame, chunks[3], view.status_line);
+    render_header(frame, chunks[0], view.agents.len(), view.vendors);
+    render_list(frame, chunks[1], &view);
+    render_detail(frame, chunks[2], view.detail);
+    render_input(frame, chunks[3], view.input_mode, view.keymap);
+    render_status(frame, chunks[4], view.status_line);
 }
 
-fn render_header(frame: &mut Frame, area: Rect, count: usize) {
-    let title = format!(" tmai-ratatui — {} agent(s) ", count);
-    let para = Paragraph::new(title).style(Style::default().add_modifier(Modifier::BOLD));
-    frame.render_widget(para, area);
+fn render_header(frame: &mut Frame, area: Rect, count: usize, vendors: &[VendorStatus]) {
+    let mut spans = vec![Span::styled(
+        format!(" tmai-ratatui — {} agent(s) ", count),
+        Style::default().add_modifier(Modifier::BOLD),
+    )];
+    for v in vendors {
+        spans.push(sep(" "));
+        spans.push(Span::styled(
+            format!("[{}]", vendor_label(v)),
+            vendor_color(&v.state),
+        ));
+    }
+    frame.render_widget(Paragraph::new(Line::from(spans)), area);
+}
+
+/// Header chip text, e.g. `claude: rate-limited until 14:05`. The resume
+/// time is shown in local time; an unparseable one is shown verbatim.
+fn vendor_label(v: &VendorStatus) -> String {
+    match &v.state {
+        VendorAvailability::Available => format!("{}: ok", v.vendor),
+        VendorAvailability::RateLimited { resume_at: None } => {
+            format!("{}: rate-limited", v.vendor)
+        }
+        VendorAvailability::RateLimited {
+            resume_at: Some(at),
+        } => {
+            let at = chrono::DateTime::parse_from_rfc3339(at)
+                .map(|t| t.with_timezone(&chrono::Local).format("%H:%M").to_string())
+                .unwrap_or_else(|_| at.clone());
+            format!("{}: rate-limited until {at}", v.vendor)
+        }
+        VendorAvailability::Unavailable { reason } if reason.is_empty() => {
+            format!("{}: unavailable", v.vendor)
+        }
+        VendorAvailability::Unavailable { reason } => {
+            format!("{}: unavailable ({reason})", v.vendor)
+        }
+        VendorAvailability::Unknown => format!("{}: ?", v.vendor),
+    }
 }
 
-fn render_list(frame: &mut Frame, area: Rect, agents: &[AgentSnapshot], selected: usize) {
-    let items: Vec<ListItem> = agents
-        .iter()
-        .map(|agent| {
-            let phase_style = phase_color(agent);
-            let phase_tag = format!("[{:^8}]", phase_label(agent));
-            let virtual_marker = if agent.is_virtual { "·" } else { " " };
-            let orch_marker = if agent.is_orchestrator { "★" } else { " " };
-            let content = Line::from(vec![
-                Span::styled(phase_tag, phase_style),
-                Span::raw(" "),
-                Span::raw(orch_marker.to_string()),
-                Span::raw(virtual_marker.to_string()),
-                Span::raw(" "),
-                Span::raw(agent.display_label.clone()),
-                Span::raw("  "),
-                Span::styled(agent.target.clone(), Style::default().fg(Color::DarkGray)),
-            ]);
-            ListItem::new(content)
-        })
-        .collect();
+fn vendor_color(state: &VendorAvailability) -> Style {
+    let base = Style::default();
+    match state {
+        VendorAvailability::Available => base.fg(Color::DarkGray),
+        VendorAvailability::RateLimited { .. } => base.fg(Color::Yellow),
+        VendorAvailability::Unavailable { .. } => base.fg(Color::Red),
+        VendorAvailability::Unknown => base,
+    }
+}
+
+fn render_list(frame: &mut Frame, area: Rect, view: &SessionListView<'_>) {
+    let show_headers = view.groups.len() > 1;
+    let now = Instant::now();
+    let mut items: Vec<ListItem> = Vec::with_capacity(view.agents.len() + view.groups.len());
+    let mut selected_row = None;
+    for (gi, group) in view.groups.iter().enumerate() {
+        let folded = view.unfolded.is_some_and(|u| u != gi);
+        if show_headers {
+            let marker = if folded { "▸" } else { "▾" };
+            let worst = Style::default().fg(attention_color(group.rollup.worst().as_ref()));
+            items.push(ListItem::new(Line::from(vec![
+                Span::styled(
+                    format!("{marker} {}", group.key),
+                    Style::default().add_modifier(Modifier::BOLD),
+                ),
+                Span::styled(format!(": {}", group.rollup.label()), worst),
+            ])));
+        }
+        if folded {
+            continue;
+        }
+        for i in group.range.clone() {
+            if i == view.selected {
+                selected_row = Some(items.len());
+            }
+            let agent = &view.agents[i];
+            let age = view.clock.label(&agent.id, now).unwrap_or_default();
+            items.push(agent_item(agent, view.pins.contains(&agent.id), age));
+        }
+    }
 
     let block = Block::default().borders(Borders::ALL).title(" agents ");
     let list = List::new(items)
@@ -86,43 +171,54 @@ fn render_list(frame: &mut Frame, area: Rect, agents: &[AgentSnapshot], selected
         .highlight_symbol("▶ ");
 
     let mut state = ListState::default();
-    if !agents.is_empty() {
-        state.select(Some(selected.min(agents.len().saturating_sub(1))));
-    }
+    state.select(selected_row);
 
     frame.render_stateful_widget(list, area, &mut state);
 }
 
-fn render_input(frame: &mut Frame, area: Rect, mode: InputModeView<'_>) {
+fn agent_item(agent: &AgentSnapshot, pinned: bool, age: String) -> ListItem<'static> {
+    let phase_style = phase_color(agent);
+    let phase_tag = format!("[{:^8}]", phase_label(agent));
+    let pin_marker = if pinned { "⚑" } else { " " };
+    let virtual_marker = if agent.is_virtual { "·" } else { " " };
+    let orch_marker = if agent.is_orchestrator { "★" } else { " " };
+    ListItem::new(Line::from(vec![
+        Span::styled(phase_tag, phase_style),
+        Span::styled(format!(" {age:>6} "), Style::default().fg(Color::DarkGray)),
+        Span::styled(pin_marker, Style::default().fg(Color::Magenta)),
+        Span::raw(orch_marker.to_string()),
+        Span::raw(virtual_marker.to_string()),
+        Span::raw(" "),
+        Span::raw(agent.display_label.clone()),
+        Span::raw("  "),
+        Span::styled(agent.target.clone(), Style::default().fg(Color::DarkGray)),
+    ]))
+}
+
+fn render_detail(frame: &mut Frame, area: Rect, detail: Option<&str>) {
+    let text = detail.map(|d| format!(" ⎇ {d}")).unwrap_or_default();
+    let para = Paragraph::new(text).style(Style::default().fg(Color::Cyan));
+    frame.render_widget(para, area);
+}
+
+fn render_input(frame: &mut Frame, area: Rect, mode: InputModeView<'_>, keymap: &Keymap) {
     let (title, body, style) = match mode {
-        InputModeView::Normal => (
-            " keys ",
-            Line::from(vec![
-                key("j/k"),
-                sep(" nav  "),
-                key("i"),
-                sep(" input  "),
-                key("a"),
-                sep(" approve  "),
-                key("y/n"),
-                sep(" yes/no  "),
-                key("K"),
-                sep(" kill  "),
-                key("r"),
-                sep(" refresh  "),
-                key("q"),
-                sep(" quit"),
-            ]),
-            Style::default(),
-        ),
-        InputModeView::Text { buffer } => (
-            " send text (Enter to send, Esc to cancel) ",
-            Line::from(buffer.to_string()),
-            Style::default().fg(Color::Yellow),
-        ),
+        InputModeView::Normal => (" keys ", key_hints(keymap).into(), Style::default()),
+        InputModeView::Text { title, buffer } => {
+            let lines: Vec<Line> = buffer
+                .split('\n')
+                .map(|l| Line::from(l.to_string()))
+                .collect();
+            let hidden = lines.len().saturating_sub(MAX_INPUT_LINES);
+            (
+                title,
+                Text::from(lines.into_iter().skip(hidden).collect::<Vec<_>>()),
+                Style::default().fg(Color::Yellow),
+            )
+        }
         InputModeView::Confirm { prompt } => (
             " confirm (y/n) ",
-            Line::from(prompt.to_string()),
+            Text::from(prompt.to_string()),
             Style::default().fg(Color::Red),
         ),
     };
@@ -157,16 +253,33 @@ fn phase_label(agent: &AgentSnapshot) -> &'static str {
 /// Started uses cyan (engaging — user just spawned, awaiting first
 /// prompt), Completed (Done) stays green, running (`None`) is gray.
 fn phase_color(agent: &AgentSnapshot) -> Style {
-    let base = Style::default();
-    match agent.attention.as_ref() {
-        Some(AgentAttention::halted) => base.fg(Color::Yellow),
-        Some(AgentAttention::completed) => base.fg(Color::Green),
-        Some(AgentAttention::started) => base.fg(Color::Cyan),
-        None => base.fg(Color::DarkGray),
+    Style::default().fg(attention_color(agent.attention.as_ref()))
+}
+
+fn attention_color(attention: Option<&AgentAttention>) -> Color {
+    match attention {
+        Some(AgentAttention::halted) => Color::Yellow,
+        Some(AgentAttention::completed) => Color::Green,
+        Some(AgentAttention::started) => Color::Cyan,
+        None => Color::DarkGray,
+    }
+}
+
+/// Footer hint line for [`Action::FOOTER`], built from the active keymap
+/// so remapped keys show their real bindings.
+fn key_hints(keymap: &Keymap) -> Line<'static> {
+    let mut spans = Vec::with_capacity(Action::FOOTER.len() * 2);
+    for (i, action) in Action::FOOTER.iter().enumerate() {
+        if i > 0 {
+            spans.push(sep("  "));
+        }
+        spans.push(key(keymap.hint(*action)));
+        spans.push(Span::raw(format!(" {}", action.label())));
     }
+    Line::from(spans)
 }
 
-fn key(k: &'static str) -> Span<'static> {
+fn key(k: impl Into<std::borrow::Cow<'static, str>>) -> Span<'static> {
     Span::styled(
         k,
         Style::default()
@@ -178,3 +291,50 @@ fn key(k: &'static str) -> Span<'static> {
 fn sep(s: &'static str) -> Span<'static> {
     Span::raw(s)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vendor(state: VendorAvailability) -> VendorStatus {
+        VendorStatus {
+            vendor: "claude".into(),
+            state,
+            account: None,
+            last_changed_at: String::new(),
+        }
+    }
+
+    #[test]
+    fn vendor_label_describes_each_state() {
+        assert_eq!(
+            vendor_label(&vendor(VendorAvailability::Available)),
+            "claude: ok"
+        );
+        assert_eq!(
+            vendor_label(&vendor(VendorAvailability::RateLimited { resume_at: None })),
+            "claude: rate-limited"
+        );
+        assert_eq!(
+            vendor_label(&vendor(VendorAvailability::RateLimited {
+                resume_at: Some("soon".into())
+            })),
+            "claude: rate-limited until soon"
+        );
+        assert_eq!(
+            vendor_label(&vendor(VendorAvailability::Unavailable {
+                reason: "auth expired".into()
+            })),
+            "claude: unavailable (auth expired)"
+        );
+    }
+
+    #[test]
+    fn default_key_hints_fit_an_80_column_box() {
+        let hints = key_hints(&Keymap::default());
+        // Two columns go to the box borders.
+        assert!(hints.width() <= 78, "{} columns", hints.width());
+        let text: String = hints.spans.iter().map(|s| s.content.as_ref()).collect();
+        assert!(text.contains("? help"), "{text}");
+    }
+}
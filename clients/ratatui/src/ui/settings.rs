@@ -0,0 +1,407 @@
+//! Settings editor overlay.
+//!
+//! Shows the tmai-core settings exposed under `/api/settings/*` as one
+//! editable list and writes changes back with a partial `PUT` per
+//! section; tmai-core persists them to its `config.toml`. Values are
+//! checked against the same bounds as the React settings page before
+//! anything is sent, so a typo never reaches the config file.
+
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph},
+    Frame,
+};
+use serde_json::{Map, Value};
+
+/// Settings sections fetched from `/api/settings/{section}`.
+pub const SECTIONS: &[&str] = &["general", "notification", "workflow", "worktree"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Bool,
+    /// Unsigned integer within inclusive bounds.
+    Number {
+        min: u64,
+        max: u64,
+    },
+    /// Free text; empty clears the key (sent as `null`).
+    OptionalText,
+}
+
+struct FieldSpec {
+    section: &'static str,
+    key: &'static str,
+    label: &'static str,
+    kind: Kind,
+}
+
+const FIELDS: &[FieldSpec] = &[
+    FieldSpec {
+        section: "general",
+        key: "default_project_root",
+        label: "Default project root",
+        kind: Kind::OptionalText,
+    },
+    FieldSpec {
+        section: "notification",
+        key: "notify_on_idle",
+        label: "Notify when an agent goes idle",
+        kind: Kind::Bool,
+    },
+    FieldSpec {
+        section: "notification",
+        key: "notify_idle_threshold_secs",
+        label: "Idle notification threshold (s)",
+        kind: Kind::Number { min: 0, max: 300 },
+    },
+    FieldSpec {
+        section: "workflow",
+        key: "auto_rebase_on_merge",
+        label: "Auto-rebase worktrees on merge",
+        kind: Kind::Bool,
+    },
+    FieldSpec {
+        section: "worktree",
+        key: "setup_timeout_secs",
+        label: "Worktree setup timeout (s)",
+        kind: Kind::Number { min: 30, max: 3600 },
+    },
+    FieldSpec {
+        section: "worktree",
+        key: "branch_depth_warning",
+        label: "Branch depth warning",
+        kind: Kind::Number { min: 1, max: 100 },
+    },
+];
+
+#[derive(Debug, Clone)]
+struct Field {
+    section: &'static str,
+    key: &'static str,
+    label: &'static str,
+    kind: Kind,
+    value: Value,
+    original: Value,
+}
+
+impl Field {
+    fn is_dirty(&self) -> bool {
+        self.value != self.original
+    }
+
+    fn display(&self) -> String {
+        match &self.value {
+            Value::Bool(true) => "on".into(),
+            Value::Bool(false) => "off".into(),
+            Value::Null => "—".into(),
+            Value::String(s) => s.clone(),
+            other => other.to_string(),
+        }
+    }
+
+    fn edit_text(&self) -> String {
+        match &self.value {
+            Value::Null => String::new(),
+            Value::String(s) => s.clone(),
+            other => other.to_string(),
+        }
+    }
+
+    /// Parse user input into a value for this field.
+    fn parse(&self, input: &str) -> Result<Value, String> {
+        let input = input.trim();
+        match self.kind {
+            Kind::Bool => match input {
+                "on" | "true" | "yes" | "1" => Ok(Value::Bool(true)),
+                "off" | "false" | "no" | "0" => Ok(Value::Bool(false)),
+                _ => Err(format!("{}: expected on/off", self.label)),
+            },
+            Kind::Number { min, max } => match input.parse::<u64>() {
+                Ok(n) if (min..=max).contains(&n) => Ok(Value::from(n)),
+                Ok(_) => Err(format!("{}: must be {min} to {max}", self.label)),
+                Err(_) => Err(format!("{}: not a number", self.label)),
+            },
+            Kind::OptionalText if input.is_empty() => Ok(Value::Null),
+            Kind::OptionalText => Ok(Value::String(input.to_string())),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SettingsView {
+    fields: Vec<Field>,
+    pub selected: usize,
+    /// In-progress text edit of the selected field.
+    pub editing: Option<String>,
+}
+
+impl SettingsView {
+    /// Build from `(section, body)` pairs as returned by
+    /// `GET /api/settings/{section}`. Keys missing from a body (older
+    /// tmai-core) are left out rather than shown with a made-up value.
+    pub fn new(sections: &[(&str, Value)]) -> Self {
+        let fields = FIELDS
+            .iter()
+            .filter_map(|spec| {
+                let body = sections
+                    .iter()
+                    .find(|(name, _)| *name == spec.section)
+                    .map(|(_, body)| body)?;
+                let value = body.get(spec.key)?.clone();
+                Some(Field {
+                    section: spec.section,
+                    key: spec.key,
+                    label: spec.label,
+                    kind: spec.kind,
+                    original: value.clone(),
+                    value,
+                })
+            })
+            .collect();
+        Self {
+            fields,
+            selected: 0,
+            editing: None,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.fields.is_empty()
+    }
+
+    pub fn select_next(&mut self) {
+        if !self.fields.is_empty() {
+            self.selected = (self.selected + 1) % self.fields.len();
+        }
+    }
+
+    pub fn select_prev(&mut self) {
+        if !self.fields.is_empty() {
+            self.selected = (self.selected + self.fields.len() - 1) % self.fields.len();
+        }
+    }
+
+    /// Enter / Space on a field: booleans toggle in place, everything else
+    /// opens the edit buffer.
+    pub fn activate(&mut self) {
+        let Some(field) = self.fields.get_mut(self.selected) else {
+            return;
+        };
+        match (&field.kind, &field.value) {
+            (Kind::Bool, Value::Bool(b)) => field.value = Value::Bool(!b),
+            _ => self.editing = Some(field.edit_text()),
+        }
+    }
+
+    /// Commit the edit buffer. On a validation error the buffer stays open.
+    pub fn commit_edit(&mut self) -> Result<(), String> {
+        let (Some(buffer), Some(field)) = (&self.editing, self.fields.get_mut(self.selected))
+        else {
+            return Ok(());
+        };
+        field.value = field.parse(buffer)?;
+        self.editing = None;
+        Ok(())
+    }
+
+    /// Revert every unsaved change.
+    pub fn discard(&mut self) {
+        for f in &mut self.fields {
+            f.value = f.original.clone();
+        }
+        self.editing = None;
+    }
+
+    pub fn dirty_count(&self) -> usize {
+        self.fields.iter().filter(|f| f.is_dirty()).count()
+    }
+
+    /// Partial `PUT` bodies, one per section with changes.
+    pub fn patches(&self) -> Vec<(&'static str, Value)> {
+        let mut out: Vec<(&'static str, Map<String, Value>)> = Vec::new();
+        for f in self.fields.iter().filter(|f| f.is_dirty()) {
+            match out.iter_mut().find(|(s, _)| *s == f.section) {
+                Some((_, map)) => {
+                    map.insert(f.key.to_string(), f.value.clone());
+                }
+                None => {
+                    let mut map = Map::new();
+                    map.insert(f.key.to_string(), f.value.clone());
+                    out.push((f.section, map));
+                }
+            }
+        }
+        out.into_iter()
+            .map(|(s, map)| (s, Value::Object(map)))
+            .collect()
+    }
+
+    /// Mark `section` as persisted after a successful `PUT`.
+    pub fn mark_saved(&mut self, section: &str) {
+        for f in self.fields.iter_mut().filter(|f| f.section == section) {
+            f.original = f.value.clone();
+        }
+    }
+}
+
+pub fn render(frame: &mut Frame, area: Rect, view: &SettingsView, status_line: &str) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Min(1),    // fields
+            Constraint::Length(3), // edit / hint box
+            Constraint::Length(1), // status
+        ])
+        .split(area);
+
+    let label_width = view
+        .fields
+        .iter()
+        .map(|f| f.label.chars().count())
+        .max()
+        .unwrap_or(0);
+    let items: Vec<ListItem> = view
+        .fields
+        .iter()
+        .map(|f| {
+            let marker = if f.is_dirty() { "*" } else { " " };
+            let value_style = if f.is_dirty() {
+                Style::default().fg(Color::Yellow)
+            } else {
+                Style::default().fg(Color::Cyan)
+            };
+            ListItem::new(Line::from(vec![
+                Span::raw(format!("{marker} {:<label_width$}  ", f.label)),
+                Span::styled(f.display(), value_style),
+                Span::styled(
+                    format!("  [{}]", f.section),
+                    Style::default().fg(Color::DarkGray),
+                ),
+            ]))
+        })
+        .collect();
+
+    let title = match view.dirty_count() {
+        0 => " settings ".to_string(),
+        n => format!(" settings — {n} unsaved ",),
+    };
+    let list = List::new(items)
+        .block(Block::default().borders(Borders::ALL).title(title))
+        .highlight_style(
+            Style::default()
+                .bg(Color::DarkGray)
+                .add_modifier(Modifier::BOLD),
+        )
+        .highlight_symbol("▶ ");
+    let mut state = ListState::default();
+    if !view.fields.is_empty() {
+        state.select(Some(view.selected.min(view.fields.len() - 1)));
+    }
+    frame.render_stateful_widget(list, chunks[0], &mut state);
+
+    let hint = match &view.editing {
+        Some(buf) => Paragraph::new(buf.clone())
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(" edit (Enter to apply, Esc to cancel) "),
+            )
+            .style(Style::default().fg(Color::Yellow)),
+        None => Paragraph::new("j/k move  Enter/Space edit/toggle  s save  u undo  Esc close")
+            .block(Block::default().borders(Borders::ALL).title(" keys ")),
+    };
+    frame.render_widget(hint, chunks[1]);
+
+    let status =
+        Paragraph::new(status_line.to_string()).style(Style::default().fg(Color::DarkGray));
+    frame.render_widget(status, chunks[2]);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn view() -> SettingsView {
+        SettingsView::new(&[
+            ("general", json!({"default_project_root": null})),
+            (
+                "notification",
+                json!({"notify_on_idle": false, "notify_idle_threshold_secs": 30}),
+            ),
+            ("workflow", json!({"auto_rebase_on_merge": true})),
+        ])
+    }
+
+    #[test]
+    fn missing_sections_are_skipped() {
+        let v = view();
+        // worktree section absent → its two fields are not shown.
+        assert_eq!(v.fields.len(), 4);
+    }
+
+    #[test]
+    fn toggling_a_bool_produces_a_partial_patch() {
+        let mut v = view();
+        v.selected = 1; // notify_on_idle
+        v.activate();
+        assert_eq!(v.dirty_count(), 1);
+        assert_eq!(
+            v.patches(),
+            vec![("notification", json!({"notify_on_idle": true}))]
+        );
+        v.mark_saved("notification");
+        assert_eq!(v.dirty_count(), 0);
+    }
+
+    #[test]
+    fn number_edit_is_validated() {
+        let mut v = view();
+        v.selected = 2; // notify_idle_threshold_secs
+        v.activate();
+        assert_eq!(v.editing.as_deref(), Some("30"));
+        v.editing = Some("301".into());
+        assert!(v.commit_edit().unwrap_err().contains("0 to 300"));
+        v.editing = Some("abc".into());
+        assert!(v.commit_edit().unwrap_err().contains("not a number"));
+        v.editing = Some(" 120 ".into());
+        v.commit_edit().unwrap();
+        assert_eq!(
+            v.patches(),
+            vec![("notification", json!({"notify_idle_threshold_secs": 120}))]
+        );
+    }
+
+    #[test]
+    fn numbers_outside_react_bounds_are_rejected() {
+        let mut v = SettingsView::new(&[(
+            "worktree",
+            json!({"setup_timeout_secs": 300, "branch_depth_warning": 5}),
+        )]);
+        for (input, ok) in [("29", false), ("30", true), ("3600", true), ("3601", false)] {
+            v.editing = Some(input.into());
+            assert_eq!(v.commit_edit().is_ok(), ok, "setup_timeout_secs={input}");
+        }
+        v.selected = 1; // branch_depth_warning
+        for (input, ok) in [("0", false), ("1", true), ("100", true), ("101", false)] {
+            v.editing = Some(input.into());
+            assert_eq!(v.commit_edit().is_ok(), ok, "branch_depth_warning={input}");
+        }
+    }
+
+    #[test]
+    fn empty_text_clears_to_null() {
+        let mut v = SettingsView::new(&[("general", json!({"default_project_root": "/src"}))]);
+        v.activate();
+        v.editing = Some(String::new());
+        v.commit_edit().unwrap();
+        assert_eq!(
+            v.patches(),
+            vec![("general", json!({"default_project_root": null}))]
+        );
+        v.discard();
+        assert_eq!(v.dirty_count(), 0);
+    }
+}
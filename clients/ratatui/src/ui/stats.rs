@@ -0,0 +1,211 @@
+//! Session summary computed from an agent's transcript.
+//!
+//! tmai-core already resolves the vendor session log behind
+//! `GET /api/agents/{id}/transcript`; this folds those records into turn
+//! counts, a per-tool call breakdown and elapsed time for the history
+//! view's stats panel (`s`).
+//!
+//! Transcript records carry no token counts, so the panel's token figure
+//! is the agent's current context-window usage from its snapshot, not a
+//! session total.
+
+use chrono::{DateTime, FixedOffset};
+
+use crate::types::{CtxUsage, TranscriptRecord};
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TranscriptStats {
+    pub user_turns: usize,
+    pub assistant_messages: usize,
+    pub tool_calls: usize,
+    pub tool_errors: usize,
+    /// `(tool name, calls)`, most used first; ties by name.
+    pub by_tool: Vec<(String, usize)>,
+    /// Span between the first and last timestamped record, in seconds.
+    pub elapsed_secs: Option<i64>,
+}
+
+impl TranscriptStats {
+    pub fn from_records(records: &[TranscriptRecord]) -> Self {
+        let mut stats = Self::default();
+        let mut first: Option<DateTime<FixedOffset>> = None;
+        let mut last: Option<DateTime<FixedOffset>> = None;
+        for record in records {
+            let timestamp = match record {
+                TranscriptRecord::User { timestamp, .. } => {
+                    stats.user_turns += 1;
+                    timestamp
+                }
+                TranscriptRecord::AssistantText { timestamp, .. } => {
+                    stats.assistant_messages += 1;
+                    timestamp
+                }
+                TranscriptRecord::Thinking { timestamp, .. } => timestamp,
+                TranscriptRecord::ToolUse {
+                    tool_name,
+                    timestamp,
+                    ..
+                } => {
+                    stats.tool_calls += 1;
+                    match stats.by_tool.iter_mut().find(|(name, _)| name == tool_name) {
+                        Some((_, n)) => *n += 1,
+                        None => stats.by_tool.push((tool_name.clone(), 1)),
+                    }
+                    timestamp
+                }
+                TranscriptRecord::ToolResult {
+                    is_error,
+                    timestamp,
+                    ..
+                } => {
+                    if *is_error {
+                        stats.tool_errors += 1;
+                    }
+                    timestamp
+                }
+                TranscriptRecord::Unknown => continue,
+            };
+            if let Some(t) = timestamp
+                .as_deref()
+                .and_then(|t| DateTime::parse_from_rfc3339(t).ok())
+            {
+                first = Some(first.map_or(t, |f| f.min(t)));
+                last = Some(last.map_or(t, |l| l.max(t)));
+            }
+        }
+        stats
+            .by_tool
+            .sort_by(|(a, x), (b, y)| y.cmp(x).then_with(|| a.cmp(b)));
+        stats.elapsed_secs = first.zip(last).map(|(f, l)| (l - f).num_seconds());
+        stats
+    }
+
+    /// Display rows for the stats panel.
+    pub fn lines(&self) -> Vec<String> {
+        let mut out = vec![
+            format!("user turns          {}", self.user_turns),
+            format!("assistant messages  {}", self.assistant_messages),
+            format!(
+                "tool calls          {} ({} failed)",
+                self.tool_calls, self.tool_errors
+            ),
+            format!(
+                "elapsed             {}",
+                self.elapsed_secs.map_or("—".to_string(), format_duration)
+            ),
+        ];
+        if !self.by_tool.is_empty() {
+            out.push(String::new());
+            let width = self
+                .by_tool
+                .iter()
+                .map(|(n, _)| n.chars().count())
+                .max()
+                .unwrap_or(0);
+            for (name, n) in &self.by_tool {
+                out.push(format!("  {name:<width$}  {n}"));
+            }
+        }
+        out
+    }
+}
+
+/// Stats panel row for the agent's context window, e.g.
+/// `context window      84k of 200k tokens (42%)`.
+pub fn context_line(ctx: &CtxUsage) -> String {
+    format!(
+        "context window      {} of {} tokens ({:.0}%)",
+        format_tokens(ctx.used),
+        format_tokens(ctx.total),
+        ctx.pct
+    )
+}
+
+/// `950`, `84k` or `1.2M`.
+fn format_tokens(n: u64) -> String {
+    match n {
+        0..=999 => n.to_string(),
+        1_000..=999_999 => format!("{}k", n / 1_000),
+        _ => format!("{:.1}M", n as f64 / 1_000_000.0),
+    }
+}
+
+/// `3h 04m`, `12m 05s` or `42s`.
+pub fn format_duration(secs: i64) -> String {
+    let secs = secs.max(0);
+    let (h, m, s) = (secs / 3600, secs % 3600 / 60, secs % 60);
+    if h > 0 {
+        format!("{h}h {m:02}m")
+    } else if m > 0 {
+        format!("{m}m {s:02}s")
+    } else {
+        format!("{s}s")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tool(name: &str, at: &str) -> TranscriptRecord {
+        TranscriptRecord::ToolUse {
+            tool_name: name.into(),
+            input_summary: String::new(),
+            input_full: None,
+            timestamp: Some(at.into()),
+        }
+    }
+
+    #[test]
+    fn counts_turns_tools_and_elapsed_time() {
+        let records = [
+            TranscriptRecord::User {
+                text: "go".into(),
+                timestamp: Some("2026-05-12T10:00:00Z".into()),
+            },
+            tool("Bash", "2026-05-12T10:00:10Z"),
+            TranscriptRecord::ToolResult {
+                output_summary: "boom".into(),
+                is_error: true,
+                timestamp: None,
+            },
+            tool("Edit", "2026-05-12T10:01:00Z"),
+            tool("Bash", "2026-05-12T10:12:05Z"),
+            TranscriptRecord::AssistantText {
+                text: "done".into(),
+                timestamp: Some("not a time".into()),
+            },
+            TranscriptRecord::Unknown,
+        ];
+        let s = TranscriptStats::from_records(&records);
+        assert_eq!((s.user_turns, s.assistant_messages), (1, 1));
+        assert_eq!((s.tool_calls, s.tool_errors), (3, 1));
+        assert_eq!(
+            s.by_tool,
+            vec![("Bash".to_string(), 2), ("Edit".to_string(), 1)]
+        );
+        assert_eq!(s.elapsed_secs, Some(725));
+    }
+
+    #[test]
+    fn context_line_shows_window_usage() {
+        let ctx = CtxUsage {
+            used: 84_321,
+            total: 200_000,
+            pct: 42.2,
+        };
+        assert_eq!(
+            context_line(&ctx),
+            "context window      84k of 200k tokens (42%)"
+        );
+        assert_eq!(format_tokens(950), "950");
+        assert_eq!(format_tokens(1_250_000), "1.2M");
+    }
+
+    #[test]
+    fn durations_are_compact() {
+        assert_eq!(format_duration(42), "42s");
+        assert_eq!(format_duration(725), "12m 05s");
+        assert_eq!(format_duration(3 * 3600 + 4 * 60 + 9), "3h 04m");
+    }
+}
@@ -0,0 +1,366 @@
+//! Prompt templates for the text input.
+//!
+//! A template is a named prompt ("commit, push and open a PR") kept in
+//! `[[prompts]]` in `ratatui.toml` or as a file in
+//! `~/.config/tmai/prompts/` (`<name>.md` or `<name>.txt`). Tab in the
+//! send-text input opens the list; the chosen template is inserted into
+//! the input for a last look before Enter sends it.
+//!
+//! `{agent}`, `{repo}`, `{branch}` and `{cwd}` are filled from the
+//! selected agent. Any other `{name}` is asked for, one at a time, before
+//! insertion. Files are read each time the list opens, so new ones show up
+//! without a restart; a config entry wins over a file of the same name.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph},
+    Frame,
+};
+use serde::Deserialize;
+
+use crate::types::AgentSnapshot;
+use crate::ui::input::PromptInput;
+use crate::ui::presets::fill;
+
+/// Variables filled from the selected agent rather than asked for.
+pub const AGENT_VARS: &[&str] = &["agent", "repo", "branch", "cwd"];
+
+/// One `[[prompts]]` entry, or one file.
+#[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct Template {
+    pub name: String,
+    pub text: String,
+}
+
+impl Template {
+    /// Placeholders to ask for, in order of first use.
+    pub fn asked_vars(&self) -> Vec<String> {
+        let mut vars: Vec<String> = Vec::new();
+        let mut rest = self.text.as_str();
+        while let Some(open) = rest.find('{') {
+            rest = &rest[open + 1..];
+            let Some(close) = rest.find('}') else { break };
+            let name = &rest[..close];
+            let valid = name.chars().next().is_some_and(|c| c.is_ascii_alphabetic())
+                && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+            if valid && !AGENT_VARS.contains(&name) && !vars.iter().any(|v| v == name) {
+                vars.push(name.to_string());
+            }
+            if valid {
+                rest = &rest[close + 1..];
+            }
+        }
+        vars
+    }
+}
+
+/// Default directory: `$XDG_CONFIG_HOME/tmai/prompts`.
+pub fn default_dir() -> Option<PathBuf> {
+    dirs::config_dir().map(|d| d.join("tmai").join("prompts"))
+}
+
+/// Templates from `*.md` and `*.txt` files in `dir`, sorted by name. A
+/// missing directory has none.
+pub fn load_dir(dir: &Path) -> Result<Vec<Template>> {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e).with_context(|| format!("read {}", dir.display())),
+    };
+    let mut templates = Vec::new();
+    for entry in entries {
+        let path = entry?.path();
+        let is_template = path
+            .extension()
+            .is_some_and(|ext| ext == "md" || ext == "txt");
+        let Some(name) = path.file_stem().and_then(|s| s.to_str()) else {
+            continue;
+        };
+        if !is_template || !path.is_file() {
+            continue;
+        }
+        let text =
+            std::fs::read_to_string(&path).with_context(|| format!("read {}", path.display()))?;
+        templates.push(Template {
+            name: name.to_string(),
+            text: text.trim_end().to_string(),
+        });
+    }
+    templates.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(templates)
+}
+
+/// Config templates in file order, then files not shadowed by one.
+pub fn merge(config: &[Template], files: Vec<Template>) -> Vec<Template> {
+    let mut all = config.to_vec();
+    all.extend(
+        files
+            .into_iter()
+            .filter(|f| !config.iter().any(|c| c.name == f.name)),
+    );
+    all
+}
+
+/// Reject duplicate names and empty prompts.
+pub fn validate(templates: &[Template]) -> Result<()> {
+    for (i, t) in templates.iter().enumerate() {
+        if t.text.trim().is_empty() {
+            bail!("prompt {:?} has no text", t.name);
+        }
+        if templates[..i].iter().any(|u| u.name == t.name) {
+            bail!("prompt {:?} is defined twice", t.name);
+        }
+    }
+    Ok(())
+}
+
+/// Values of [`AGENT_VARS`] for `agent`.
+pub fn agent_vars(agent: &AgentSnapshot) -> Vec<(String, String)> {
+    let name = if agent.display_label.is_empty() {
+        &agent.id
+    } else {
+        &agent.display_label
+    };
+    let repo = agent
+        .cwd
+        .trim_end_matches('/')
+        .rsplit('/')
+        .next()
+        .unwrap_or("");
+    vec![
+        ("agent".into(), name.clone()),
+        ("repo".into(), repo.into()),
+        (
+            "branch".into(),
+            agent.git_branch.clone().unwrap_or_default(),
+        ),
+        ("cwd".into(), agent.cwd.clone()),
+    ]
+}
+
+/// Values being typed in for a template's asked variables.
+#[derive(Debug, Clone)]
+pub struct Filling {
+    pub vars: Vec<String>,
+    pub values: Vec<String>,
+    pub buffer: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct TemplatesView {
+    pub templates: Vec<Template>,
+    pub selected: usize,
+    /// Filled from the selected agent.
+    pub vars: Vec<(String, String)>,
+    pub filling: Option<Filling>,
+    /// The input this list was opened from, restored on close.
+    pub input: PromptInput,
+}
+
+impl TemplatesView {
+    pub fn new(templates: Vec<Template>, vars: Vec<(String, String)>, input: PromptInput) -> Self {
+        Self {
+            templates,
+            selected: 0,
+            vars,
+            filling: None,
+            input,
+        }
+    }
+
+    pub fn select_next(&mut self) {
+        if !self.templates.is_empty() {
+            self.selected = (self.selected + 1) % self.templates.len();
+        }
+    }
+
+    pub fn select_prev(&mut self) {
+        if !self.templates.is_empty() {
+            self.selected = (self.selected + self.templates.len() - 1) % self.templates.len();
+        }
+    }
+
+    /// Jump straight to template `n` (1-based).
+    pub fn select_number(&mut self, n: usize) {
+        if (1..=self.templates.len()).contains(&n) {
+            self.selected = n - 1;
+        }
+    }
+
+    /// Pick the selected template: its text when nothing needs asking,
+    /// otherwise `None` and start asking.
+    pub fn choose(&mut self) -> Option<String> {
+        let vars = self.templates.get(self.selected)?.asked_vars();
+        if vars.is_empty() {
+            return self.filled(&[]);
+        }
+        self.filling = Some(Filling {
+            vars,
+            values: Vec::new(),
+            buffer: String::new(),
+        });
+        None
+    }
+
+    /// Take the typed value; the text once the last one is in.
+    pub fn submit_value(&mut self) -> Option<String> {
+        let mut filling = self.filling.take()?;
+        filling
+            .values
+            .push(std::mem::take(&mut filling.buffer).trim().to_string());
+        if filling.values.len() < filling.vars.len() {
+            self.filling = Some(filling);
+            return None;
+        }
+        let asked: Vec<(String, String)> = filling.vars.into_iter().zip(filling.values).collect();
+        self.filled(&asked)
+    }
+
+    fn filled(&self, asked: &[(String, String)]) -> Option<String> {
+        let template = self.templates.get(self.selected)?;
+        let vars: Vec<(&str, &str)> = self
+            .vars
+            .iter()
+            .chain(asked)
+            .map(|(k, v)| (k.as_str(), v.as_str()))
+            .collect();
+        Some(fill(&template.text, &vars))
+    }
+}
+
+pub fn render(frame: &mut Frame, area: Rect, view: &TemplatesView, status_line: &str) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Min(1),    // templates
+            Constraint::Length(3), // hint / value box
+            Constraint::Length(1), // status
+        ])
+        .split(area);
+
+    let items: Vec<ListItem> = view
+        .templates
+        .iter()
+        .enumerate()
+        .map(|(i, t)| {
+            let first_line = t.text.lines().next().unwrap_or("");
+            ListItem::new(Line::from(vec![
+                Span::styled(format!("{}. ", i + 1), Style::default().fg(Color::DarkGray)),
+                Span::styled(t.name.clone(), Style::default().fg(Color::Cyan)),
+                Span::styled(
+                    format!("  {first_line}"),
+                    Style::default().fg(Color::DarkGray),
+                ),
+            ]))
+        })
+        .collect();
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(" prompt templates "),
+        )
+        .highlight_style(
+            Style::default()
+                .bg(Color::DarkGray)
+                .add_modifier(Modifier::BOLD),
+        )
+        .highlight_symbol("▶ ");
+    let mut state = ListState::default();
+    if !view.templates.is_empty() {
+        state.select(Some(view.selected.min(view.templates.len() - 1)));
+    }
+    frame.render_stateful_widget(list, chunks[0], &mut state);
+
+    let hint = match &view.filling {
+        Some(f) => Paragraph::new(f.buffer.clone())
+            .block(Block::default().borders(Borders::ALL).title(format!(
+                " {{{}}} ({}/{}; Enter next, Esc back) ",
+                f.vars[f.values.len()],
+                f.values.len() + 1,
+                f.vars.len()
+            )))
+            .style(Style::default().fg(Color::Yellow)),
+        None => Paragraph::new("j/k or 1-9 pick  Enter insert  Esc back to input")
+            .block(Block::default().borders(Borders::ALL).title(" keys ")),
+    };
+    frame.render_widget(hint, chunks[1]);
+
+    frame.render_widget(
+        Paragraph::new(status_line.to_string()).style(Style::default().fg(Color::DarkGray)),
+        chunks[2],
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn template(name: &str, text: &str) -> Template {
+        Template {
+            name: name.into(),
+            text: text.into(),
+        }
+    }
+
+    #[test]
+    fn asks_only_for_unknown_variables_once() {
+        let t = template(
+            "pr",
+            "Commit on {branch}, push, open a PR titled {title} for {issue}; {title}. {not a var} {}",
+        );
+        assert_eq!(t.asked_vars(), ["title", "issue"]);
+    }
+
+    #[test]
+    fn choose_fills_agent_vars_then_asked_ones() {
+        let vars = vec![("branch".to_string(), "feat-x".to_string())];
+        let templates = vec![
+            template("push", "push {branch}"),
+            template("pr", "PR from {branch}: {title}"),
+        ];
+        let mut v = TemplatesView::new(templates, vars, PromptInput::default());
+        assert_eq!(v.choose().as_deref(), Some("push feat-x"));
+
+        v.select_number(2);
+        assert!(v.choose().is_none());
+        v.filling.as_mut().unwrap().buffer = " Fix login ".into();
+        assert_eq!(
+            v.submit_value().as_deref(),
+            Some("PR from feat-x: Fix login")
+        );
+        assert!(v.filling.is_none());
+    }
+
+    #[test]
+    fn files_load_sorted_and_config_wins() {
+        let dir = std::env::temp_dir().join(format!("tmai-prompts-dir-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("ship.md"), "commit and push\n").unwrap();
+        std::fs::write(dir.join("review.txt"), "review {branch}").unwrap();
+        std::fs::write(dir.join("notes.json"), "{}").unwrap();
+        let files = load_dir(&dir).unwrap();
+        let names: Vec<_> = files.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, ["review", "ship"]);
+        assert_eq!(files[1].text, "commit and push");
+
+        let all = merge(&[template("ship", "ship it")], files);
+        let texts: Vec<_> = all.iter().map(|t| t.text.as_str()).collect();
+        assert_eq!(texts, ["ship it", "review {branch}"]);
+        assert!(load_dir(&dir.join("missing")).unwrap().is_empty());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn validate_rejects_duplicates_and_empty_text() {
+        validate(&[template("a", "x"), template("b", "y")]).unwrap();
+        assert!(validate(&[template("a", "x"), template("a", "y")]).is_err());
+        assert!(validate(&[template("a", " ")]).is_err());
+    }
+}
@@ -0,0 +1,375 @@
+//! Worktree manager overlay.
+//!
+//! Lists the git worktrees tmai-core tracks (`GET /api/worktrees`) with
+//! their branch, dirty state, diff size and attached agent, and drives the
+//! lifecycle endpoints: `POST /api/spawn/worktree` creates a worktree +
+//! branch with an agent in it, `POST /api/worktrees/delete` removes one.
+//! Worktrees with no agent attached whose branch has nothing left to
+//! merge into its parent (`GET /api/git/branches`, ahead count 0) are
+//! flagged as stale so leftovers are easy to spot and clean up. An idle
+//! worktree with unmerged commits, or whose branch counts couldn't be
+//! fetched, is tagged `idle` instead.
+
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph},
+    Frame,
+};
+use std::collections::HashMap;
+
+use serde_json::Value;
+
+use crate::types::{BranchListResponse, WorktreeSnapshot};
+
+/// Pending prompt inside the overlay.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WorktreePrompt {
+    /// Waiting for y/n before deleting the selected worktree. `force` is
+    /// set when it has uncommitted changes.
+    ConfirmDelete { force: bool },
+    /// Typing the name of a new worktree (branch) to create.
+    NewName(String),
+}
+
+#[derive(Debug, Clone)]
+pub struct WorktreesView {
+    pub worktrees: Vec<WorktreeSnapshot>,
+    /// `/git/branches` per `repo_path`; repos whose fetch failed are absent.
+    pub branches: HashMap<String, BranchListResponse>,
+    pub selected: usize,
+    pub prompt: Option<WorktreePrompt>,
+}
+
+impl WorktreesView {
+    pub fn new(
+        mut worktrees: Vec<WorktreeSnapshot>,
+        branches: HashMap<String, BranchListResponse>,
+    ) -> Self {
+        sort(&mut worktrees);
+        Self {
+            worktrees,
+            branches,
+            selected: 0,
+            prompt: None,
+        }
+    }
+
+    /// Replace the list after a refetch, keeping the selection on the
+    /// same worktree when it still exists.
+    pub fn reload(
+        &mut self,
+        mut worktrees: Vec<WorktreeSnapshot>,
+        branches: HashMap<String, BranchListResponse>,
+    ) {
+        sort(&mut worktrees);
+        let path = self.current().map(|w| w.path.clone());
+        self.worktrees = worktrees;
+        self.branches = branches;
+        self.selected = path
+            .and_then(|p| self.worktrees.iter().position(|w| w.path == p))
+            .unwrap_or(0)
+            .min(self.worktrees.len().saturating_sub(1));
+    }
+
+    pub fn current(&self) -> Option<&WorktreeSnapshot> {
+        self.worktrees.get(self.selected)
+    }
+
+    /// Whether `w` is stale; see [`is_stale`].
+    pub fn stale(&self, w: &WorktreeSnapshot) -> bool {
+        is_stale(w, self.branches.get(&w.repo_path))
+    }
+
+    pub fn select_next(&mut self) {
+        if !self.worktrees.is_empty() {
+            self.selected = (self.selected + 1) % self.worktrees.len();
+        }
+    }
+
+    pub fn select_prev(&mut self) {
+        if !self.worktrees.is_empty() {
+            self.selected = (self.selected + self.worktrees.len() - 1) % self.worktrees.len();
+        }
+    }
+
+    /// Start the delete confirmation. The main worktree can't be removed.
+    pub fn request_delete(&mut self) -> Result<(), &'static str> {
+        let w = self.current().ok_or("no worktree selected")?;
+        if w.is_main {
+            return Err("the main worktree can't be deleted");
+        }
+        self.prompt = Some(WorktreePrompt::ConfirmDelete { force: is_dirty(w) });
+        Ok(())
+    }
+}
+
+/// Group by repository, main worktree first, then by name.
+fn sort(worktrees: &mut [WorktreeSnapshot]) {
+    worktrees.sort_by(|a, b| {
+        (&a.repo_name, !a.is_main, &a.name).cmp(&(&b.repo_name, !b.is_main, &b.name))
+    });
+}
+
+pub fn branch(w: &WorktreeSnapshot) -> Option<&str> {
+    w.branch.as_ref().and_then(Value::as_str)
+}
+
+pub fn is_dirty(w: &WorktreeSnapshot) -> bool {
+    matches!(w.is_dirty, Some(Value::Bool(true)))
+}
+
+pub fn agent_target(w: &WorktreeSnapshot) -> Option<&str> {
+    w.agent_target.as_ref().and_then(Value::as_str)
+}
+
+/// A linked worktree with no agent running or starting in it.
+pub fn is_idle(w: &WorktreeSnapshot) -> bool {
+    !w.is_main && agent_target(w).is_none() && w.agent_pending != Some(true)
+}
+
+/// Whether `w`'s branch has no commits its parent lacks. `None` when
+/// that isn't known: no branch, or no counts for it in `branches`.
+pub fn is_merged(w: &WorktreeSnapshot, branches: Option<&BranchListResponse>) -> Option<bool> {
+    let (ahead, _) = branches?.ahead_behind.get(branch(w)?)?;
+    Some(*ahead == 0)
+}
+
+/// An idle worktree whose branch is already merged into its parent, so
+/// deleting it loses nothing that was committed.
+pub fn is_stale(w: &WorktreeSnapshot, branches: Option<&BranchListResponse>) -> bool {
+    is_idle(w) && is_merged(w, branches) == Some(true)
+}
+
+/// Compact `+12 -3 (2 files)` for a worktree's diff against its base.
+pub fn diff_label(w: &WorktreeSnapshot) -> Option<String> {
+    let d = w.diff_summary.as_ref()?;
+    if d.files_changed == 0 {
+        return None;
+    }
+    let files = if d.files_changed == 1 {
+        "file"
+    } else {
+        "files"
+    };
+    Some(format!(
+        "+{} -{} ({} {files})",
+        d.insertions, d.deletions, d.files_changed
+    ))
+}
+
+/// Confirmation text for deleting `w`, naming the agent that would lose
+/// its working directory.
+pub fn delete_prompt(w: &WorktreeSnapshot, force: bool) -> String {
+    let name = &w.name;
+    let agent = agent_target(w)
+        .map(|t| format!(" Agent {t} is still attached."))
+        .unwrap_or_default();
+    if force {
+        format!("Delete {name} and DISCARD its uncommitted changes?{agent} [y/N]")
+    } else {
+        format!("Delete worktree {name} and its branch?{agent} [y/N]")
+    }
+}
+
+pub fn render(frame: &mut Frame, area: Rect, view: &WorktreesView, status_line: &str) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Min(1),    // worktrees
+            Constraint::Length(3), // prompt / hint box
+            Constraint::Length(1), // status
+        ])
+        .split(area);
+
+    let items: Vec<ListItem> = view
+        .worktrees
+        .iter()
+        .map(|w| {
+            let (tag, tag_style) = if w.is_main {
+                ("main", Style::default().fg(Color::Cyan))
+            } else if w.agent_pending == Some(true) {
+                ("starting", Style::default().fg(Color::Yellow))
+            } else if view.stale(w) {
+                ("stale", Style::default().fg(Color::DarkGray))
+            } else if is_idle(w) {
+                ("idle", Style::default().fg(Color::Blue))
+            } else {
+                ("agent", Style::default().fg(Color::Green))
+            };
+            let mut spans = vec![
+                Span::styled(format!("[{tag:^8}]"), tag_style),
+                Span::raw(format!(" {}/{}", w.repo_name, w.name)),
+            ];
+            if let Some(b) = branch(w).filter(|b| *b != w.name) {
+                spans.push(Span::styled(
+                    format!("  ({b})"),
+                    Style::default().fg(Color::DarkGray),
+                ));
+            }
+            if is_dirty(w) {
+                spans.push(Span::styled("  *dirty", Style::default().fg(Color::Yellow)));
+            }
+            if let Some(d) = diff_label(w) {
+                spans.push(Span::raw(format!("  {d}")));
+            }
+            if let Some(t) = agent_target(w) {
+                spans.push(Span::styled(
+                    format!("  {t}"),
+                    Style::default().fg(Color::DarkGray),
+                ));
+            }
+            ListItem::new(Line::from(spans))
+        })
+        .collect();
+
+    let stale = view.worktrees.iter().filter(|w| view.stale(w)).count();
+    let title = match stale {
+        0 => format!(" worktrees ({}) ", view.worktrees.len()),
+        n => format!(" worktrees ({}, {n} stale) ", view.worktrees.len()),
+    };
+    let list = List::new(items)
+        .block(Block::default().borders(Borders::ALL).title(title))
+        .highlight_style(
+            Style::default()
+                .bg(Color::DarkGray)
+                .add_modifier(Modifier::BOLD),
+        )
+        .highlight_symbol("▶ ");
+    let mut state = ListState::default();
+    if !view.worktrees.is_empty() {
+        state.select(Some(view.selected.min(view.worktrees.len() - 1)));
+    }
+    frame.render_stateful_widget(list, chunks[0], &mut state);
+
+    let hint = match &view.prompt {
+        Some(WorktreePrompt::ConfirmDelete { force }) => {
+            let text = view
+                .current()
+                .map(|w| delete_prompt(w, *force))
+                .unwrap_or_default();
+            Paragraph::new(text)
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .title(" confirm (y/n) "),
+                )
+                .style(Style::default().fg(Color::Red))
+        }
+        Some(WorktreePrompt::NewName(buf)) => Paragraph::new(buf.clone())
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(" new worktree name (Enter to create + launch agent, Esc to cancel) "),
+            )
+            .style(Style::default().fg(Color::Yellow)),
+        None => Paragraph::new("j/k move  n new  D delete  r reload  Esc close")
+            .block(Block::default().borders(Borders::ALL).title(" keys ")),
+    };
+    frame.render_widget(hint, chunks[1]);
+
+    let status =
+        Paragraph::new(status_line.to_string()).style(Style::default().fg(Color::DarkGray));
+    frame.render_widget(status, chunks[2]);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn wt(value: Value) -> WorktreeSnapshot {
+        serde_json::from_value(value).unwrap()
+    }
+
+    fn sample() -> Vec<WorktreeSnapshot> {
+        vec![
+            wt(json!({
+                "name": "feat-x", "path": "/r/.wt/feat-x", "repo_name": "r",
+                "repo_path": "/r", "is_main": false, "branch": "feat-x",
+                "is_dirty": true, "agent_target": "main:1.0",
+                "diff_summary": {"files_changed": 2, "insertions": 12, "deletions": 3}
+            })),
+            wt(json!({
+                "name": "old", "path": "/r/.wt/old", "repo_name": "r",
+                "repo_path": "/r", "is_main": false, "branch": "old", "agent_target": null
+            })),
+            wt(json!({
+                "name": "r", "path": "/r", "repo_name": "r", "repo_path": "/r", "is_main": true
+            })),
+        ]
+    }
+
+    fn view(old_ahead: u64) -> WorktreesView {
+        let mut b = BranchListResponse::default();
+        b.ahead_behind.insert("feat-x".into(), (4, 0));
+        b.ahead_behind.insert("old".into(), (old_ahead, 7));
+        WorktreesView::new(sample(), HashMap::from([("/r".to_string(), b)]))
+    }
+
+    #[test]
+    fn main_sorts_first_and_stale_is_flagged() {
+        let v = view(0);
+        let names: Vec<_> = v.worktrees.iter().map(|w| w.name.as_str()).collect();
+        assert_eq!(names, ["r", "feat-x", "old"]);
+        let stale: Vec<_> = v.worktrees.iter().map(|w| v.stale(w)).collect();
+        assert_eq!(stale, [false, false, true]);
+        assert_eq!(
+            diff_label(&v.worktrees[1]).as_deref(),
+            Some("+12 -3 (2 files)")
+        );
+    }
+
+    #[test]
+    fn idle_worktree_with_unmerged_commits_is_not_stale() {
+        let v = view(2);
+        assert!(is_idle(&v.worktrees[2]));
+        assert_eq!(
+            is_merged(&v.worktrees[2], v.branches.get("/r")),
+            Some(false)
+        );
+        assert!(!v.stale(&v.worktrees[2]));
+
+        // Without branch counts the merge state is unknown.
+        let v = WorktreesView::new(sample(), HashMap::new());
+        assert_eq!(is_merged(&v.worktrees[2], None), None);
+        assert!(!v.stale(&v.worktrees[2]));
+    }
+
+    #[test]
+    fn delete_prompt_names_the_attached_agent() {
+        let v = view(0);
+        assert_eq!(
+            delete_prompt(&v.worktrees[1], true),
+            "Delete feat-x and DISCARD its uncommitted changes? \
+             Agent main:1.0 is still attached. [y/N]"
+        );
+        assert_eq!(
+            delete_prompt(&v.worktrees[2], false),
+            "Delete worktree old and its branch? [y/N]"
+        );
+    }
+
+    #[test]
+    fn delete_refuses_main_and_forces_dirty() {
+        let mut v = view(0);
+        assert!(v.request_delete().is_err());
+        v.select_next();
+        v.request_delete().unwrap();
+        assert_eq!(
+            v.prompt,
+            Some(WorktreePrompt::ConfirmDelete { force: true })
+        );
+    }
+
+    #[test]
+    fn reload_keeps_selection_by_path() {
+        let mut v = view(0);
+        v.selected = 2; // old
+        let mut fewer = sample();
+        fewer.remove(0); // feat-x gone
+        v.reload(fewer, HashMap::new());
+        assert_eq!(v.current().unwrap().name, "old");
+    }
+}